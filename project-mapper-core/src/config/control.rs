@@ -0,0 +1,29 @@
+use serde::{Deserialize, Serialize};
+
+use super::source::SourceType;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "type")]
+pub enum SnapshotTarget {
+    // what the sink currently shows, all regions composited
+    Sink { id: u32 },
+    // the source's output before any region scales it
+    Source { id: u32 },
+}
+
+/// Requests accepted by a running runtime, sent as one JSON object per line
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(tag = "type")]
+pub enum ControlRequest {
+    // change the settings of a running source, the source type must stay the same
+    UpdateSource { id: u32, source: SourceType },
+    // write the current frame of a sink or source as a PNG, the path is on the runtime's machine
+    Snapshot { target: SnapshotTarget, path: String },
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(tag = "type")]
+pub enum ControlResponse {
+    Ok {},
+    Error { message: String },
+}
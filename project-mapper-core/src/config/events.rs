@@ -3,6 +3,20 @@ use super::options::SinkTypeOptions;
 pub enum RuntimeEvent {
     UserExit(),
     StopThread(),
+    // a source posted an error and its regions switched to the fallback
+    SourceFailed { id: u32, message: String },
+    SourceRetrying { id: u32, attempt: u32, delay_ms: u64 },
+    // retries are exhausted, the fallback stays up
+    SourceGaveUp { id: u32, attempts: u32 },
+    SourceRecovered { id: u32 },
+    MonitorConnected { name: String },
+    MonitorDisconnected { name: String },
+    // a window sink was put back on its monitor, or on the fallback one, after monitors changed
+    SinkMonitorChanged { sink: String, monitor: String },
+    // the monitor of a window sink is gone and there is no fallback, the window keeps rendering
+    SinkMonitorMissing { sink: String, message: String },
+    // an element outside the sources failed, the pipeline keeps running unless it's fatal
+    PipelineError { element: String, message: String },
 }
 
 pub enum OptionEvent {
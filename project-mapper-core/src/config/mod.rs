@@ -10,3 +10,9 @@ pub mod sink;
 
 #[path = "./options.rs"]
 pub mod options;
+
+#[path = "./control.rs"]
+pub mod control;
+
+#[path = "./venue.rs"]
+pub mod venue;
@@ -3,8 +3,11 @@ use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
 
 use super::{
-    sink::{MonitorInfo, RefreshRate, Resolution, ResolutionJson, SinkConfig, SinkType},
-    source::{SourceConfig, SourceType},
+    sink::{
+        MonitorDescriptor, MonitorInfo, RefreshRate, Resolution, ResolutionJson, SinkConfig,
+        SinkType,
+    },
+    source::{AudioOutput, SourceConfig, SourceType},
 };
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -16,6 +19,9 @@ pub enum RegionType {
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct WindowOptions {}
 
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct OutputOptions {}
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct BorderlessOptions {
     pub monitors: Vec<String>,
@@ -35,6 +41,7 @@ pub enum FullscreenOptions {
     Windowed(WindowOptions),
     Borderless(BorderlessOptions),
     Exclusive(ExclusiveOptions),
+    Output(OutputOptions),
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -42,14 +49,60 @@ pub enum FullscreenOptions {
 pub enum SinkTypeOptions {
     OpenGLWindow {
         full_screen_modes: Vec<FullscreenOptions>,
+        // descriptors of the monitors the modes name
+        #[serde(default)]
+        monitors: Vec<MonitorDescriptor>,
+    },
+    File {
+        formats: Vec<String>,
     },
+    Stream {
+        protocols: Vec<String>,
+    },
+    SharedMemory {},
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CaptureMode {
+    pub media_type: String,
+    pub format: Option<String>,
+    pub resolution: Option<Resolution>,
+    pub framerates: Vec<u32>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CaptureDeviceInfo {
+    pub name: String,
+    // value to use for `Capture::device`
+    pub device: String,
+    pub modes: Vec<CaptureMode>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(tag = "type")]
 pub enum SourceTypeOptions {
-    Test {},
-    URI { uri_types: Vec<String> },
+    Test {
+        patterns: Vec<String>,
+        overlays: Vec<String>,
+    },
+    URI {
+        uri_types: Vec<String>,
+        // Auto and Fake are always usable, devices only when the device monitor found them
+        audio_outputs: Vec<AudioOutput>,
+    },
+    Capture {
+        devices: Vec<CaptureDeviceInfo>,
+    },
+    Screen {
+        areas: Vec<String>,
+    },
+    Pipeline {},
+    Color {},
+    Text {},
+    NetworkStream { protocols: Vec<String> },
+    ImageSequence { formats: Vec<String> },
+    SharedMemory {},
+    Custom { kind: String, settings: serde_json::Value },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -8,7 +8,13 @@ use super::{
 #[derive(Serialize, Deserialize)]
 #[serde(tag = "type")]
 pub enum RegionType {
-    Display { source: u32, sink: u32 },
+    Display {
+        source: u32,
+        sink: u32,
+        // regions on the same sink are stacked by layer, higher layers drawn over lower ones
+        #[serde(default)]
+        layer: u32,
+    },
 }
 
 #[derive(Serialize, Deserialize)]
@@ -48,18 +48,563 @@ pub struct MonitorInfo {
     pub refresh_rate_hz: RefreshRate,
 }
 
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct WindowPosition {
+    pub x: i32,
+    pub y: i32,
+}
+
+/// Everything known about a monitor, names alone change between machines and reboots
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct MonitorDescriptor {
+    pub name: String,
+    // identifier from the platform where it has one, e.g. the display id on macOS
+    #[serde(default)]
+    pub native_id: Option<String>,
+    // top left corner on the desktop
+    #[serde(default)]
+    pub position: Option<WindowPosition>,
+    #[serde(default)]
+    pub native_resolution: Option<Resolution>,
+    // place counting from the left most monitor, 0 is the left most
+    #[serde(default)]
+    pub index: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum MonitorRule {
+    NativeId,
+    Name,
+    Position,
+    NativeResolution,
+    Index,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub enum MonitorFallback {
+    // the sink fails to open
+    #[default]
+    Fail,
+    Primary,
+    // any monitor that is left
+    Any,
+}
+
+impl MonitorRule {
+    /// Whether `found` is the monitor `wanted` describes as far as this rule can tell
+    pub fn matches(&self, wanted: &MonitorDescriptor, found: &MonitorDescriptor) -> bool {
+        match self {
+            MonitorRule::NativeId => {
+                wanted.native_id.is_some() && found.native_id == wanted.native_id
+            }
+            MonitorRule::Name => found.name == wanted.name,
+            MonitorRule::Position => wanted.position.is_some() && found.position == wanted.position,
+            MonitorRule::NativeResolution => {
+                wanted.native_resolution.is_some()
+                    && found.native_resolution == wanted.native_resolution
+            }
+            MonitorRule::Index => wanted.index.is_some() && found.index == wanted.index,
+        }
+    }
+}
+
+fn default_monitor_rules() -> Vec<MonitorRule> {
+    vec![
+        MonitorRule::NativeId,
+        MonitorRule::Name,
+        MonitorRule::Position,
+        MonitorRule::NativeResolution,
+        MonitorRule::Index,
+    ]
+}
+
+/// Finds a monitor by its descriptor. Rules are tried in order, a rule matching a single
+/// monitor picks it and a rule matching several narrows the candidates for the next rules.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct MonitorMatch {
+    pub descriptor: MonitorDescriptor,
+    #[serde(default = "default_monitor_rules")]
+    pub rules: Vec<MonitorRule>,
+    #[serde(default)]
+    pub fallback: MonitorFallback,
+}
+
+impl MonitorMatch {
+    pub fn new(descriptor: MonitorDescriptor) -> Self {
+        MonitorMatch {
+            descriptor: descriptor,
+            rules: default_monitor_rules(),
+            fallback: MonitorFallback::default(),
+        }
+    }
+
+    /// Picks one of `monitors` and returns its position in the slice, `primary` is the position
+    /// of the primary monitor if there is one
+    pub fn find(&self, monitors: &[MonitorDescriptor], primary: Option<usize>) -> Option<usize> {
+        self.find_named(None, monitors, primary)
+    }
+
+    /// Like `find`, but a monitor called `name` wins when the rules can't tell monitors apart
+    /// or match none of them
+    pub fn find_named(
+        &self,
+        name: Option<&str>,
+        monitors: &[MonitorDescriptor],
+        primary: Option<usize>,
+    ) -> Option<usize> {
+        let named = |candidates: &[usize]| {
+            candidates
+                .iter()
+                .copied()
+                .find(|&position| Some(monitors[position].name.as_str()) == name)
+        };
+        let mut candidates: Vec<usize> = (0..monitors.len()).collect();
+        candidates.sort_by_key(|&position| monitors[position].index);
+        for rule in &self.rules {
+            let matching: Vec<usize> = candidates
+                .iter()
+                .copied()
+                .filter(|&position| rule.matches(&self.descriptor, &monitors[position]))
+                .collect();
+            match matching.len() {
+                0 => {}
+                1 => return Some(matching[0]),
+                _ => candidates = matching,
+            }
+        }
+
+        // rules that narrowed things down are better than the fallback, take the left most
+        if candidates.len() < monitors.len() {
+            return named(&candidates).or(candidates.first().copied());
+        }
+        if let Some(position) = named(&candidates) {
+            return Some(position);
+        }
+        match self.fallback {
+            MonitorFallback::Fail => None,
+            MonitorFallback::Primary => primary,
+            MonitorFallback::Any => candidates.first().copied(),
+        }
+    }
+}
+
+fn default_decorations() -> bool {
+    true
+}
+
+/// Where and how a windowed sink opens, unset fields are left to the window manager
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct WindowGeometry {
+    // monitor the position is relative to, positions are on the whole desktop when unset
+    #[serde(default)]
+    pub monitor: Option<String>,
+    #[serde(default)]
+    pub position: Option<WindowPosition>,
+    // inner size of the window
+    #[serde(default)]
+    pub size: Option<Resolution>,
+    #[serde(default = "default_decorations")]
+    pub decorations: bool,
+    #[serde(default)]
+    pub always_on_top: bool,
+}
+
+impl Default for WindowGeometry {
+    fn default() -> Self {
+        WindowGeometry {
+            monitor: None,
+            position: None,
+            size: None,
+            decorations: default_decorations(),
+            always_on_top: false,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(tag = "type")]
 pub enum FullScreenMode {
-    Windowed {},
+    Windowed(WindowGeometry),
     Borderless { name: String },
     Exclusive { info: MonitorInfo },
+    // logical output like "stage-left", the venue profile says which monitor and mode it is
+    Output { name: String },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub enum RecordingFormat {
+    // H.264 in MP4, small files for sharing previews
+    #[default]
+    Mp4,
+    // lossless FFV1 in Matroska, for comparing renders
+    Matroska,
+}
+
+/// Encodes the composited output to a file instead of showing it
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Recording {
+    pub path: String,
+    #[serde(default)]
+    pub format: RecordingFormat,
+    pub resolution: Resolution,
+    pub framerate: u32,
+}
+
+impl Default for Recording {
+    fn default() -> Self {
+        Recording {
+            path: String::new(),
+            format: RecordingFormat::default(),
+            resolution: Resolution {
+                width: 1920,
+                height: 1080,
+            },
+            framerate: 30,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub enum OutputProtocol {
+    // RTP over UDP
+    #[default]
+    Rtp,
+    Srt,
+}
+
+fn default_bitrate() -> u32 {
+    8000
+}
+
+fn default_latency() -> u32 {
+    120
+}
+
+/// Encodes the composited output as H.264 and sends it over the network
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct StreamOutput {
+    #[serde(default)]
+    pub protocol: OutputProtocol,
+    // udp://host:port or srt://host:port, srt://:port?mode=listener waits for the receiver
+    pub uri: String,
+    pub resolution: Resolution,
+    pub framerate: u32,
+    #[serde(default = "default_bitrate")]
+    pub bitrate_kbps: u32,
+    // only used by SRT, the receiver has to use the same value
+    #[serde(default = "default_latency")]
+    pub latency_ms: u32,
+}
+
+impl Default for StreamOutput {
+    fn default() -> Self {
+        StreamOutput {
+            protocol: OutputProtocol::default(),
+            uri: String::new(),
+            resolution: Resolution {
+                width: 1920,
+                height: 1080,
+            },
+            framerate: 30,
+            bitrate_kbps: default_bitrate(),
+            latency_ms: default_latency(),
+        }
+    }
+}
+
+fn default_buffer_frames() -> u32 {
+    4
+}
+
+/// Publishes raw RGBA frames for other processes on the same machine through `shmsink`.
+/// Readers use `video/x-raw,format=RGBA,width=<width>,height=<height>,framerate=<framerate>/1`
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SharedMemoryOutput {
+    // control socket readers connect to, created by the runtime
+    pub socket_path: String,
+    pub resolution: Resolution,
+    pub framerate: u32,
+    // frames the shared memory area holds before a slow reader starts dropping them
+    #[serde(default = "default_buffer_frames")]
+    pub buffer_frames: u32,
+}
+
+impl Default for SharedMemoryOutput {
+    fn default() -> Self {
+        SharedMemoryOutput {
+            socket_path: String::new(),
+            resolution: Resolution {
+                width: 1920,
+                height: 1080,
+            },
+            framerate: 30,
+            buffer_frames: default_buffer_frames(),
+        }
+    }
+}
+
+/// Keeps a projector window clean while a show runs
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct KioskOptions {
+    #[serde(default)]
+    pub hide_cursor: bool,
+    // in every full screen mode, not just windowed
+    #[serde(default)]
+    pub always_on_top: bool,
+    // screen blanking and DPMS stay off while the window is open, X11 only
+    #[serde(default)]
+    pub inhibit_screensaver: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub enum DropPolicy {
+    // the pipeline waits for the window, every frame is shown
+    #[default]
+    Block,
+    // the newest frame replaces the oldest queued one, lowest latency for live cameras
+    DropOldest,
+}
+
+fn default_vsync() -> bool {
+    true
+}
+
+fn default_swap_interval() -> u32 {
+    1
+}
+
+fn default_queue_depth() -> u32 {
+    1
+}
+
+/// Trades latency against smoothness for a window sink
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct FramePacing {
+    #[serde(default = "default_vsync")]
+    pub vsync: bool,
+    // vertical blanks per swap when vsync is on, 2 halves the refresh rate
+    #[serde(default = "default_swap_interval")]
+    pub swap_interval: u32,
+    // frames queued in front of the window
+    #[serde(default = "default_queue_depth")]
+    pub queue_depth: u32,
+    #[serde(default)]
+    pub drop_policy: DropPolicy,
+    // framerate the sink's regions are mixed at, follows the sources when not set
+    #[serde(default)]
+    pub framerate: Option<u32>,
+}
+
+impl Default for FramePacing {
+    fn default() -> Self {
+        FramePacing {
+            vsync: default_vsync(),
+            swap_interval: default_swap_interval(),
+            queue_depth: default_queue_depth(),
+            drop_policy: DropPolicy::default(),
+            framerate: None,
+        }
+    }
+}
+
+/// Canvas pixels hidden behind the bezels between neighbouring monitors of a span, so lines
+/// crossing from one monitor to the next stay straight
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct Bezel {
+    #[serde(default)]
+    pub horizontal: u32,
+    #[serde(default)]
+    pub vertical: u32,
+}
+
+/// One monitor of a span
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SpanTile {
+    pub full_screen: FullScreenMode,
+    #[serde(default)]
+    pub monitor: Option<MonitorMatch>,
+}
+
+/// Part of a span's canvas one monitor shows, in canvas pixels
+#[derive(Clone, Debug, PartialEq)]
+pub struct TileArea {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// One canvas shown across a grid of monitors, e.g. a video wall or a wide blend
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Span {
+    pub canvas: Resolution,
+    pub columns: u32,
+    // listed row by row, left to right
+    pub tiles: Vec<SpanTile>,
+    #[serde(default)]
+    pub bezel: Bezel,
+    // applied to every tile window
+    #[serde(default)]
+    pub kiosk: KioskOptions,
+    #[serde(default)]
+    pub pacing: FramePacing,
+}
+
+impl Default for Span {
+    fn default() -> Self {
+        Span {
+            canvas: Resolution {
+                width: 3840,
+                height: 1080,
+            },
+            columns: 2,
+            tiles: vec![],
+            bezel: Bezel::default(),
+            kiosk: KioskOptions::default(),
+            pacing: FramePacing::default(),
+        }
+    }
+}
+
+impl Span {
+    /// Checks there is a grid and that the bezels leave room for the tiles
+    pub fn validate(&self) -> Result<()> {
+        if self.columns == 0 {
+            return Err(Error::msg("Span needs at least one column"));
+        }
+        if self.tiles.is_empty() {
+            return Err(Error::msg("Span has no tiles"));
+        }
+        // every tile is the same size so checking the first is enough
+        let area = self.tile_area(0);
+        if area.width == 0 || area.height == 0 {
+            return Err(Error::msg(format!(
+                "{} columns and {} rows with {}x{} bezels leave no room on a {} canvas",
+                self.columns,
+                self.rows(),
+                self.bezel.horizontal,
+                self.bezel.vertical,
+                self.canvas.to_json()
+            )));
+        }
+        Ok(())
+    }
+
+    pub fn rows(&self) -> u32 {
+        (self.tiles.len() as u32).div_ceil(self.columns.max(1))
+    }
+
+    /// The canvas is split evenly after taking out the bezels between tiles
+    pub fn tile_area(&self, index: usize) -> TileArea {
+        let columns = self.columns.max(1);
+        let rows = self.rows().max(1);
+        let width = self
+            .canvas
+            .width
+            .saturating_sub((columns - 1).saturating_mul(self.bezel.horizontal))
+            / columns;
+        let height = self
+            .canvas
+            .height
+            .saturating_sub((rows - 1).saturating_mul(self.bezel.vertical))
+            / rows;
+        let column = index as u32 % columns;
+        let row = index as u32 / columns;
+        TileArea {
+            x: column * (width + self.bezel.horizontal),
+            y: row * (height + self.bezel.vertical),
+            width: width,
+            height: height,
+        }
+    }
+}
+
+/// Part of the frame a window shows, as fractions of the frame size from the top left
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Crop {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl Crop {
+    pub fn from_tile(area: &TileArea, canvas: &Resolution) -> Crop {
+        Crop {
+            x: area.x as f32 / canvas.width as f32,
+            y: area.y as f32 / canvas.height as f32,
+            width: area.width as f32 / canvas.width as f32,
+            height: area.height as f32 / canvas.height as f32,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone)]
 #[serde(tag = "type")]
 pub enum SinkType {
-    OpenGLWindow { full_screen: FullScreenMode },
+    OpenGLWindow {
+        full_screen: FullScreenMode,
+        // resolution regions are laid out against, scaled to fit the window when it differs
+        #[serde(default)]
+        canvas: Option<Resolution>,
+        // finds the monitor named by the full screen mode when names have changed
+        #[serde(default)]
+        monitor: Option<MonitorMatch>,
+        #[serde(default)]
+        kiosk: KioskOptions,
+        #[serde(default)]
+        pacing: FramePacing,
+    },
+    File(Recording),
+    Stream(StreamOutput),
+    SharedMemory(SharedMemoryOutput),
+    Span(Span),
+}
+
+impl SinkType {
+    /// Whether the sink needs a window, a config without any can run headless
+    pub fn needs_window(&self) -> bool {
+        match self {
+            SinkType::OpenGLWindow { .. } | SinkType::Span(_) => true,
+            SinkType::File(_) | SinkType::Stream(_) | SinkType::SharedMemory(_) => false,
+        }
+    }
+
+    /// Checks the settings that can't be drawn before anything is built for the sink
+    pub fn validate(&self) -> Result<()> {
+        if let Some(canvas) = self.canvas() {
+            if canvas.width == 0 || canvas.height == 0 {
+                return Err(Error::msg(format!(
+                    "Canvas {} has no area",
+                    canvas.to_json()
+                )));
+            }
+        }
+        if let SinkType::Span(span) = self {
+            span.validate()?;
+        }
+        Ok(())
+    }
+
+    /// Fixed resolution the sink's regions are composited at, if it has one
+    pub fn canvas(&self) -> Option<&Resolution> {
+        match self {
+            SinkType::OpenGLWindow { canvas, .. } => canvas.as_ref(),
+            SinkType::File(recording) => Some(&recording.resolution),
+            SinkType::Stream(output) => Some(&output.resolution),
+            SinkType::SharedMemory(output) => Some(&output.resolution),
+            SinkType::Span(span) => Some(&span.canvas),
+        }
+    }
+
+    /// Frame pacing of sinks that show frames in windows
+    pub fn pacing(&self) -> Option<&FramePacing> {
+        match self {
+            SinkType::OpenGLWindow { pacing, .. } => Some(pacing),
+            SinkType::Span(span) => Some(&span.pacing),
+            SinkType::File(_) | SinkType::Stream(_) | SinkType::SharedMemory(_) => None,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone)]
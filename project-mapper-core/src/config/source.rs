@@ -3,19 +3,397 @@ use std::sync::{Arc, Mutex};
 use anyhow::Error;
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize)]
-pub struct Test {}
+use super::sink::Resolution;
 
-#[derive(Serialize, Deserialize)]
+/// Text burnt into a test source so it can double as a sync check
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub enum TestOverlay {
+    #[default]
+    None,
+    Timecode,
+    Clock,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct Test {
+    // nick of a `videotestsrc` pattern e.g. "smpte" or "ball"
+    pub pattern: Option<String>,
+    pub resolution: Option<Resolution>,
+    pub framerate: Option<u32>,
+    #[serde(default)]
+    pub overlay: TestOverlay,
+}
+
+fn default_rate() -> f64 {
+    1.0
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Playback {
+    // positions in the media, the out point defaults to the end
+    pub in_point_ms: Option<u64>,
+    pub out_point_ms: Option<u64>,
+    // where playback starts relative to the in point, or the out point when reversed
+    #[serde(default)]
+    pub start_offset_ms: u64,
+    // negative rates play in reverse if the demuxer supports it
+    #[serde(default = "default_rate")]
+    pub rate: f64,
+}
+
+impl Default for Playback {
+    fn default() -> Self {
+        Playback {
+            in_point_ms: None,
+            out_point_ms: None,
+            start_offset_ms: 0,
+            rate: default_rate(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct URI {
     pub uri: String,
+    #[serde(default)]
+    pub playback: Playback,
+    // keep the alpha channel of formats like VP9 or ProRes 4444 so the source can be layered
+    #[serde(default)]
+    pub alpha: bool,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct Capture {
+    // device path such as /dev/video0 or the display name reported by the device monitor
+    pub device: String,
+    pub resolution: Option<Resolution>,
+    pub framerate: Option<u32>,
+    // full caps string, takes precedence over resolution and framerate e.g. "image/jpeg,width=1280,height=720"
+    pub caps: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(tag = "type")]
+pub enum ScreenArea {
+    #[default]
+    FullScreen,
+    Region {
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    },
+    // match a window by X11 id or by its title
+    Window {
+        id: Option<u64>,
+        name: Option<String>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct Screen {
+    // X11 display e.g. ":0", defaults to $DISPLAY
+    pub display: Option<String>,
+    #[serde(default)]
+    pub area: ScreenArea,
+    #[serde(default)]
+    pub show_cursor: bool,
+    pub framerate: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct Pipeline {
+    // gst-launch style description whose unlinked src pad provides video
+    // e.g. "videotestsrc pattern=ball ! videoflip method=horizontal-flip"
+    pub description: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub enum StreamProtocol {
+    #[default]
+    Rtsp,
+    Srt,
+    // RTP over UDP
+    Rtp,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub enum StreamTransport {
+    #[default]
+    Auto,
+    Tcp,
+    Udp,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct NetworkStream {
+    #[serde(default)]
+    pub protocol: StreamProtocol,
+    // rtsp://host/path, srt://host:port or udp://host:port
+    pub uri: String,
+    // jitter buffer size
+    pub latency_ms: Option<u32>,
+    // only used by RTSP
+    #[serde(default)]
+    pub transport: StreamTransport,
+    // time without data before the stream is considered lost
+    pub timeout_ms: Option<u64>,
+    // RTP can't be discovered so needs caps
+    // e.g. "application/x-rtp,media=video,encoding-name=H264,clock-rate=90000,payload=96"
+    pub caps: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct ImageSequence {
+    // folder holding the frames
+    pub directory: String,
+    // printf style file name of a frame e.g. "frame_%05d.png", the extension picks the decoder
+    pub pattern: String,
+    pub framerate: Option<u32>,
+    #[serde(default)]
+    pub start_frame: u32,
+    // last frame to play, defaults to the last file found
+    pub end_frame: Option<u32>,
+    #[serde(default, rename = "loop")]
+    pub looping: bool,
+    // decoded frames buffered ahead of playback to hide slow reads
+    pub preload_frames: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct SharedMemory {
+    // control socket of the producer's shmsink
+    pub socket_path: String,
+    // raw video caps the producer writes
+    // e.g. "video/x-raw,format=RGBA,width=1920,height=1080,framerate=30/1"
+    pub caps: String,
+}
+
+// source kind registered by an application embedding the runtime
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct Custom {
+    // name the kind was registered under
+    pub kind: String,
+    // parsed by the registered kind
+    #[serde(default)]
+    pub settings: serde_json::Value,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Rgba {
+    pub red: u8,
+    pub green: u8,
+    pub blue: u8,
+    pub alpha: u8,
+}
+
+impl Rgba {
+    pub const BLACK: Rgba = Rgba {
+        red: 0,
+        green: 0,
+        blue: 0,
+        alpha: 255,
+    };
+    pub const WHITE: Rgba = Rgba {
+        red: 255,
+        green: 255,
+        blue: 255,
+        alpha: 255,
+    };
+
+    /// Packs the color the way GStreamer color properties expect it
+    pub fn to_argb(&self) -> u32 {
+        u32::from_be_bytes([self.alpha, self.red, self.green, self.blue])
+    }
+}
+
+// opaque like the serde defaults, a zero alpha would make sources invisible
+impl Default for Rgba {
+    fn default() -> Self {
+        Rgba::BLACK
+    }
+}
+
+fn default_black() -> Rgba {
+    Rgba::BLACK
+}
+
+fn default_white() -> Rgba {
+    Rgba::WHITE
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Color {
+    #[serde(default = "default_black")]
+    pub color: Rgba,
+    pub resolution: Option<Resolution>,
+    pub framerate: Option<u32>,
+}
+
+impl Default for Color {
+    fn default() -> Self {
+        Color {
+            color: default_black(),
+            resolution: None,
+            framerate: None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub enum HorizontalAlignment {
+    Left,
+    #[default]
+    Center,
+    Right,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub enum VerticalAlignment {
+    Top,
+    #[default]
+    Center,
+    Bottom,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Text {
+    pub text: String,
+    // pango font family and style e.g. "Sans Bold"
+    pub font: Option<String>,
+    // font size in points
+    pub size: Option<u32>,
+    #[serde(default = "default_white")]
+    pub color: Rgba,
+    #[serde(default = "default_black")]
+    pub background: Rgba,
+    #[serde(default)]
+    pub horizontal_alignment: HorizontalAlignment,
+    #[serde(default)]
+    pub vertical_alignment: VerticalAlignment,
+    pub resolution: Option<Resolution>,
+    pub framerate: Option<u32>,
+}
+
+impl Default for Text {
+    fn default() -> Self {
+        Text {
+            text: String::new(),
+            font: None,
+            size: None,
+            color: default_white(),
+            background: default_black(),
+            horizontal_alignment: HorizontalAlignment::default(),
+            vertical_alignment: VerticalAlignment::default(),
+            resolution: None,
+            framerate: None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(tag = "type")]
 pub enum SourceType {
     Test(Test),
     URI(URI),
+    Capture(Capture),
+    Screen(Screen),
+    Pipeline(Pipeline),
+    Color(Color),
+    Text(Text),
+    NetworkStream(NetworkStream),
+    ImageSequence(ImageSequence),
+    SharedMemory(SharedMemory),
+    Custom(Custom),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(tag = "type")]
+pub enum AudioOutput {
+    #[default]
+    Auto,
+    // display name of a device listed by `get-available-config`
+    Device {
+        name: String,
+    },
+    // keeps clock sync but discards samples, used for headless runs
+    Fake,
+}
+
+fn default_volume() -> f64 {
+    1.0
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct AudioConfig {
+    #[serde(default)]
+    pub output: AudioOutput,
+    #[serde(default = "default_volume")]
+    pub volume: f64,
+    #[serde(default)]
+    pub mute: bool,
+    // positive values delay the audio relative to the video
+    #[serde(default)]
+    pub offset_ms: i64,
+}
+
+impl Default for AudioConfig {
+    fn default() -> Self {
+        AudioConfig {
+            output: AudioOutput::default(),
+            volume: default_volume(),
+            mute: false,
+            offset_ms: 0,
+        }
+    }
+}
+
+// shown in the source's regions while it is down
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(tag = "type")]
+pub enum Fallback {
+    #[default]
+    Black,
+    // still image, the file extension picks the decoder like image sequences
+    Image {
+        path: String,
+    },
+    // another source by id
+    Source {
+        id: u32,
+    },
+}
+
+fn default_retry_delay() -> u64 {
+    1000
+}
+
+fn default_max_retry_delay() -> u64 {
+    30000
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct FailurePolicy {
+    // retries before giving up, retries forever when unset
+    pub max_retries: Option<u32>,
+    // doubled after every failed retry up to the max delay
+    #[serde(default = "default_retry_delay")]
+    pub retry_delay_ms: u64,
+    #[serde(default = "default_max_retry_delay")]
+    pub max_retry_delay_ms: u64,
+    #[serde(default)]
+    pub fallback: Fallback,
+}
+
+impl Default for FailurePolicy {
+    fn default() -> Self {
+        FailurePolicy {
+            max_retries: None,
+            retry_delay_ms: default_retry_delay(),
+            max_retry_delay_ms: default_max_retry_delay(),
+            fallback: Fallback::default(),
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize)]
@@ -23,4 +401,8 @@ pub struct SourceConfig {
     pub name: String,
     pub id: u32,
     pub source: SourceType,
+    // audio is dropped unless configured
+    pub audio: Option<AudioConfig>,
+    #[serde(default)]
+    pub failure: FailurePolicy,
 }
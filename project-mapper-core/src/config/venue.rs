@@ -0,0 +1,105 @@
+//! Venue profiles map the logical outputs a show targets to the monitors of one machine, so a show
+//! moves between venues by swapping the profile instead of editing every sink.
+
+use anyhow::{Error, Result};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    options::{AvailableConfig, SinkTypeOptions},
+    runtime::RuntimeConfig,
+    sink::{FullScreenMode, MonitorMatch, SinkType},
+};
+
+/// File name of the profile the runtime looks for next to a show file
+pub const VENUE_FILE_NAME: &str = "venue.json";
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct VenueProfile {
+    pub outputs: Vec<VenueOutput>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct VenueOutput {
+    pub name: String,
+    pub full_screen: FullScreenMode,
+    #[serde(default)]
+    pub monitor: Option<MonitorMatch>,
+}
+
+impl VenueProfile {
+    /// A profile with a borderless output per connected monitor, numbered from the left. Rename
+    /// the outputs to the names the show uses.
+    pub fn from_available_config(config: &AvailableConfig) -> VenueProfile {
+        let mut descriptors = vec![];
+        for sink in &config.sinks {
+            if let SinkTypeOptions::OpenGLWindow { monitors, .. } = sink {
+                descriptors.extend(monitors.iter().cloned());
+            }
+        }
+        descriptors.sort_by_key(|descriptor| descriptor.index);
+
+        let outputs = descriptors
+            .into_iter()
+            .enumerate()
+            .map(|(number, descriptor)| VenueOutput {
+                name: format!("output-{}", number + 1),
+                full_screen: FullScreenMode::Borderless {
+                    name: descriptor.name.clone(),
+                },
+                monitor: Some(MonitorMatch::new(descriptor)),
+            })
+            .collect();
+        VenueProfile { outputs: outputs }
+    }
+
+    /// Replaces the logical outputs window sinks target with this venue's monitors and modes
+    pub fn apply(&self, config: &mut RuntimeConfig) -> Result<()> {
+        for sink in config.sinks.iter_mut() {
+            match &mut sink.sink {
+                SinkType::OpenGLWindow {
+                    full_screen,
+                    monitor,
+                    ..
+                } => self.resolve(&sink.name, full_screen, monitor)?,
+                SinkType::Span(span) => {
+                    for tile in span.tiles.iter_mut() {
+                        self.resolve(&sink.name, &mut tile.full_screen, &mut tile.monitor)?;
+                    }
+                }
+                SinkType::File(_) | SinkType::Stream(_) | SinkType::SharedMemory(_) => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn resolve(
+        &self,
+        sink_name: &str,
+        full_screen: &mut FullScreenMode,
+        monitor: &mut Option<MonitorMatch>,
+    ) -> Result<()> {
+        let FullScreenMode::Output { name } = full_screen else {
+            return Ok(());
+        };
+
+        let output = self
+            .outputs
+            .iter()
+            .find(|output| output.name == *name)
+            .ok_or_else(|| {
+                let output_names: Vec<&String> =
+                    self.outputs.iter().map(|output| &output.name).collect();
+                Error::msg(format!(
+                    "Sink {sink_name} targets output {name}, the venue maps {output_names:?}"
+                ))
+            })?;
+        if let FullScreenMode::Output { .. } = output.full_screen {
+            return Err(Error::msg(format!(
+                "Venue output {name} must name a monitor, not another output"
+            )));
+        }
+        *full_screen = output.full_screen.clone();
+        *monitor = output.monitor.clone();
+        Ok(())
+    }
+}
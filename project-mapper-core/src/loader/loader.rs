@@ -1,7 +1,9 @@
 use anyhow::Result;
 use std::fs;
+use std::path::{Path, PathBuf};
 
 use crate::config::runtime::RuntimeConfig;
+use crate::config::venue::{VENUE_FILE_NAME, VenueProfile};
 
 pub fn load_config(path: &String) -> Result<RuntimeConfig> {
     let data = fs::read_to_string(path)?;
@@ -17,3 +19,16 @@ pub fn export_config(config: &RuntimeConfig) -> Result<String> {
     let result = serde_json::to_string(config)?;
     Ok(result)
 }
+
+pub fn load_venue(path: &Path) -> Result<VenueProfile> {
+    let data = fs::read_to_string(path)?;
+    Ok(serde_json::from_str(&data)?)
+}
+
+/// Where the venue profile of a show file is looked for when none is given
+pub fn venue_path(config_path: &str) -> PathBuf {
+    Path::new(config_path)
+        .parent()
+        .unwrap_or(Path::new(""))
+        .join(VENUE_FILE_NAME)
+}
@@ -0,0 +1,107 @@
+use project_mapper_core::config::sink::{
+    MonitorDescriptor, MonitorFallback, MonitorMatch, MonitorRule, Resolution, WindowPosition,
+};
+
+fn monitor(name: &str, x: i32, index: u32) -> MonitorDescriptor {
+    MonitorDescriptor {
+        name: name.to_owned(),
+        native_id: None,
+        position: Some(WindowPosition { x: x, y: 0 }),
+        native_resolution: Some(Resolution {
+            width: 1920,
+            height: 1080,
+        }),
+        index: Some(index),
+    }
+}
+
+/// Two projectors of the same model report the same name, only their position tells them apart
+fn identical_projectors() -> Vec<MonitorDescriptor> {
+    vec![
+        monitor("DP-1", 0, 0),
+        monitor("Projector", 1920, 1),
+        monitor("Projector", 3840, 2),
+    ]
+}
+
+#[test]
+fn name_picks_a_unique_monitor() {
+    let monitor_match = MonitorMatch::new(MonitorDescriptor {
+        name: "DP-1".to_owned(),
+        ..Default::default()
+    });
+    assert_eq!(monitor_match.find(&identical_projectors(), None), Some(0));
+}
+
+#[test]
+fn position_tells_identical_names_apart() {
+    let monitor_match = MonitorMatch::new(monitor("Projector", 3840, 2));
+    assert_eq!(monitor_match.find(&identical_projectors(), None), Some(2));
+}
+
+#[test]
+fn narrowed_candidates_take_the_left_most() {
+    // position and index moved, the name still narrows it down to both projectors
+    let monitor_match = MonitorMatch::new(monitor("Projector", 7680, 5));
+    assert_eq!(monitor_match.find(&identical_projectors(), None), Some(1));
+}
+
+#[test]
+fn rules_run_in_order() {
+    let mut monitor_match = MonitorMatch::new(monitor("DP-1", 3840, 0));
+    monitor_match.rules = vec![MonitorRule::Position, MonitorRule::Name];
+    assert_eq!(monitor_match.find(&identical_projectors(), None), Some(2));
+    monitor_match.rules = vec![MonitorRule::Name, MonitorRule::Position];
+    assert_eq!(monitor_match.find(&identical_projectors(), None), Some(0));
+}
+
+#[test]
+fn native_id_wins_over_everything_else() {
+    let mut monitors = identical_projectors();
+    monitors[1].native_id = Some("42".to_owned());
+    let mut wanted = monitor("DP-1", 3840, 2);
+    wanted.native_id = Some("42".to_owned());
+    assert_eq!(MonitorMatch::new(wanted).find(&monitors, None), Some(1));
+}
+
+#[test]
+fn fallbacks_apply_when_nothing_matches() {
+    let mut wanted = monitor("HDMI-1", 100, 9);
+    wanted.native_resolution = None;
+    let mut monitor_match = MonitorMatch::new(wanted);
+    let monitors = identical_projectors();
+
+    monitor_match.fallback = MonitorFallback::Fail;
+    assert_eq!(monitor_match.find(&monitors, Some(1)), None);
+    monitor_match.fallback = MonitorFallback::Primary;
+    assert_eq!(monitor_match.find(&monitors, Some(1)), Some(1));
+    assert_eq!(monitor_match.find(&monitors, None), None);
+    monitor_match.fallback = MonitorFallback::Any;
+    assert_eq!(monitor_match.find(&monitors, None), Some(0));
+    assert_eq!(monitor_match.find(&[], None), None);
+}
+
+#[test]
+fn name_breaks_ties_and_beats_the_fallback() {
+    let mut monitors = identical_projectors();
+    monitors[0].native_resolution = Some(Resolution {
+        width: 3840,
+        height: 2160,
+    });
+    monitors[2].name = "Projector 2".to_owned();
+    // the resolution narrows it down to both projectors
+    let mut monitor_match = MonitorMatch::new(monitor("Projector", 7680, 5));
+    monitor_match.rules = vec![MonitorRule::NativeResolution];
+    assert_eq!(monitor_match.find(&monitors, None), Some(1));
+    assert_eq!(
+        monitor_match.find_named(Some("Projector 2"), &monitors, None),
+        Some(2)
+    );
+
+    monitor_match.rules = vec![MonitorRule::Position];
+    assert_eq!(monitor_match.find(&monitors, None), None);
+    assert_eq!(
+        monitor_match.find_named(Some("Projector 2"), &monitors, None),
+        Some(2)
+    );
+}
@@ -0,0 +1,132 @@
+use project_mapper_core::config::sink::{
+    Bezel, Crop, FullScreenMode, Resolution, Span, SpanTile, TileArea,
+};
+
+fn span(width: u32, height: u32, columns: u32, tiles: usize, bezel: Bezel) -> Span {
+    Span {
+        canvas: Resolution {
+            width: width,
+            height: height,
+        },
+        columns: columns,
+        tiles: (0..tiles)
+            .map(|index| SpanTile {
+                full_screen: FullScreenMode::Output {
+                    name: format!("wall-{index}"),
+                },
+                monitor: None,
+            })
+            .collect(),
+        bezel: bezel,
+        ..Span::default()
+    }
+}
+
+#[test]
+fn tiles_split_the_canvas_around_the_bezels() {
+    let wall = span(
+        3860,
+        2180,
+        2,
+        4,
+        Bezel {
+            horizontal: 20,
+            vertical: 20,
+        },
+    );
+    assert!(wall.validate().is_ok());
+    assert_eq!(wall.rows(), 2);
+    assert_eq!(
+        wall.tile_area(0),
+        TileArea {
+            x: 0,
+            y: 0,
+            width: 1920,
+            height: 1080,
+        }
+    );
+    assert_eq!(
+        wall.tile_area(3),
+        TileArea {
+            x: 1940,
+            y: 1100,
+            width: 1920,
+            height: 1080,
+        }
+    );
+}
+
+#[test]
+fn a_partial_last_row_still_counts_as_a_row() {
+    let wall = span(3000, 2000, 3, 4, Bezel::default());
+    assert_eq!(wall.rows(), 2);
+    assert_eq!(
+        wall.tile_area(3),
+        TileArea {
+            x: 0,
+            y: 1000,
+            width: 1000,
+            height: 1000,
+        }
+    );
+}
+
+#[test]
+fn spans_without_room_for_tiles_are_rejected() {
+    let no_columns = span(3840, 1080, 0, 2, Bezel::default());
+    assert!(no_columns.validate().is_err());
+
+    let no_tiles = span(3840, 1080, 2, 0, Bezel::default());
+    assert!(no_tiles.validate().is_err());
+
+    let wide_bezel = span(
+        3840,
+        1080,
+        2,
+        2,
+        Bezel {
+            horizontal: 3840,
+            vertical: 0,
+        },
+    );
+    assert!(wide_bezel.validate().is_err());
+
+    let huge_bezel = span(
+        3840,
+        2160,
+        2,
+        4,
+        Bezel {
+            horizontal: 0,
+            vertical: u32::MAX,
+        },
+    );
+    assert!(huge_bezel.validate().is_err());
+
+    // more columns than canvas pixels
+    let narrow = span(3, 1080, 4, 4, Bezel::default());
+    assert!(narrow.validate().is_err());
+}
+
+#[test]
+fn crop_is_the_tile_as_a_fraction_of_the_canvas() {
+    let canvas = Resolution {
+        width: 4000,
+        height: 2000,
+    };
+    let area = TileArea {
+        x: 2000,
+        y: 500,
+        width: 1000,
+        height: 1500,
+    };
+    assert_eq!(
+        Crop::from_tile(&area, &canvas),
+        Crop {
+            x: 0.5,
+            y: 0.25,
+            width: 0.25,
+            height: 0.75,
+        }
+    );
+}
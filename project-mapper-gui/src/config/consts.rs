@@ -1,3 +1,4 @@
 pub const WINDOWED_FULLSCREEN_MODE: &str = "Windowed";
 pub const BORDERLESS_FULLSCREEN_MODE: &str = "Borderless";
 pub const EXCLUSIVE_FULLSCREEN_MODE: &str = "Exclusive";
+pub const OUTPUT_FULLSCREEN_MODE: &str = "Output";
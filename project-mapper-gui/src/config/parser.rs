@@ -2,25 +2,120 @@ use std::{collections::HashMap, hash::Hash};
 
 use anyhow::{Error, Result};
 use project_mapper_core::config::{
-    options::MonitorResolutionRefreshRateMap,
-    sink::{RefreshRate, ResolutionJson},
+    options::{CaptureDeviceInfo, MonitorResolutionRefreshRateMap},
+    sink::{MonitorDescriptor, RefreshRate, ResolutionJson},
 };
 
 #[derive(Clone, Debug)]
 pub struct ParsedAvailableConfig {
     pub full_screen_modes: Vec<String>,
     pub monitors: MonitorResolutionRefreshRateMap,
+    pub monitor_descriptors: Vec<MonitorDescriptor>,
+    pub test_patterns: Vec<String>,
+    pub test_overlays: Vec<String>,
+    pub capture_devices: Vec<CaptureDeviceInfo>,
+    pub stream_protocols: Vec<String>,
+    pub recording_formats: Vec<String>,
+    pub output_protocols: Vec<String>,
 }
 
 impl ParsedAvailableConfig {
     pub fn new(config: &json::JsonValue) -> Result<ParsedAvailableConfig> {
         let modes = ParsedAvailableConfig::extract_fullscreen_types(config)?;
         let monitors = ParsedAvailableConfig::extract_monitor_info(config)?;
+        let monitor_descriptors = ParsedAvailableConfig::extract_monitor_descriptors(config)?;
+        let test_patterns = ParsedAvailableConfig::extract_test_option(config, "patterns")?;
+        let test_overlays = ParsedAvailableConfig::extract_test_option(config, "overlays")?;
+        let capture_devices = ParsedAvailableConfig::extract_capture_devices(config)?;
+        let stream_protocols = ParsedAvailableConfig::extract_stream_protocols(config)?;
+        let recording_formats = ParsedAvailableConfig::extract_recording_formats(config)?;
+        let output_protocols = ParsedAvailableConfig::extract_output_protocols(config)?;
         Ok(ParsedAvailableConfig {
             full_screen_modes: modes,
             monitors: monitors,
+            monitor_descriptors: monitor_descriptors,
+            test_patterns: test_patterns,
+            test_overlays: test_overlays,
+            capture_devices: capture_devices,
+            stream_protocols: stream_protocols,
+            recording_formats: recording_formats,
+            output_protocols: output_protocols,
         })
     }
+
+    pub fn extract_output_protocols(config: &json::JsonValue) -> Result<Vec<String>> {
+        let mut protocols = vec![];
+        for data in config["sinks"].members() {
+            if data["type"] != "Stream" {
+                continue;
+            }
+
+            for protocol in data["protocols"].members() {
+                let protocol_string = protocol.as_str().ok_or(Error::msg("expected a string"))?;
+                protocols.push(String::from(protocol_string));
+            }
+        }
+        Ok(protocols)
+    }
+
+    pub fn extract_recording_formats(config: &json::JsonValue) -> Result<Vec<String>> {
+        let mut formats = vec![];
+        for data in config["sinks"].members() {
+            if data["type"] != "File" {
+                continue;
+            }
+
+            for format in data["formats"].members() {
+                let format_string = format.as_str().ok_or(Error::msg("expected a string"))?;
+                formats.push(String::from(format_string));
+            }
+        }
+        Ok(formats)
+    }
+
+    pub fn extract_stream_protocols(config: &json::JsonValue) -> Result<Vec<String>> {
+        let mut protocols = vec![];
+        for data in config["sources"].members() {
+            if data["type"] != "NetworkStream" {
+                continue;
+            }
+
+            for protocol in data["protocols"].members() {
+                let protocol_string = protocol.as_str().ok_or(Error::msg("expected a string"))?;
+                protocols.push(String::from(protocol_string));
+            }
+        }
+        Ok(protocols)
+    }
+
+    pub fn extract_capture_devices(config: &json::JsonValue) -> Result<Vec<CaptureDeviceInfo>> {
+        let mut devices = vec![];
+        for data in config["sources"].members() {
+            if data["type"] != "Capture" {
+                continue;
+            }
+
+            for device in data["devices"].members() {
+                devices.push(serde_json::from_str(&device.dump())?);
+            }
+        }
+        Ok(devices)
+    }
+
+    pub fn extract_test_option(config: &json::JsonValue, field: &str) -> Result<Vec<String>> {
+        let mut values = vec![];
+        for data in config["sources"].members() {
+            if data["type"] != "Test" {
+                continue;
+            }
+
+            for value in data[field].members() {
+                let value_string = value.as_str().ok_or(Error::msg("expected a string"))?;
+                values.push(String::from(value_string));
+            }
+        }
+        Ok(values)
+    }
     pub fn extract_fullscreen_types(config: &json::JsonValue) -> Result<Vec<String>> {
         let mut modes = vec![];
         for data in config["sinks"].members() {
@@ -36,6 +131,20 @@ impl ParsedAvailableConfig {
         Ok(modes)
     }
 
+    pub fn extract_monitor_descriptors(config: &json::JsonValue) -> Result<Vec<MonitorDescriptor>> {
+        let mut descriptors = vec![];
+        for data in config["sinks"].members() {
+            if data["type"] != "OpenGLWindow" {
+                continue;
+            }
+
+            for descriptor in data["monitors"].members() {
+                descriptors.push(serde_json::from_str(&descriptor.dump())?);
+            }
+        }
+        Ok(descriptors)
+    }
+
     pub fn extract_monitor_info(
         config: &json::JsonValue,
     ) -> Result<MonitorResolutionRefreshRateMap> {
@@ -7,7 +7,7 @@ use eframe::{
 use project_mapper_core::config::{
     runtime::{RegionConfig, RegionType, RuntimeConfig},
     sink::{MonitorInfo, Resolution, SinkConfig, SinkType},
-    source::{SourceConfig, SourceType, Test, URI},
+    source::{FailurePolicy, SourceConfig, SourceType, Test, URI},
 };
 use rand::distr::slice::Empty;
 
@@ -77,6 +77,7 @@ impl SimpleUiCore {
                                     name: name,
                                     data: ElementData::Source(SourceElementType::Empty()),
                                     data_type: SourceElementType::Empty().to_string(),
+                                    failure: FailurePolicy::default(),
                                 });
                             }
                             UiElementInfo::Region { id, name } => {
@@ -86,6 +87,7 @@ impl SimpleUiCore {
 
                                     data: ElementData::Region(RegionElementType::Empty()),
                                     data_type: RegionElementType::Empty().to_string(),
+                                    failure: FailurePolicy::default(),
                                 });
                             }
                             UiElementInfo::Sink { id, name } => {
@@ -94,6 +96,7 @@ impl SimpleUiCore {
                                     name: name,
                                     data: ElementData::Sink(SinkElementType::Empty()),
                                     data_type: SinkElementType::Empty().to_string(),
+                                    failure: FailurePolicy::default(),
                                 });
                             }
                         },
@@ -139,9 +142,41 @@ impl CoreView for &mut SimpleUiCore {
                             id: id,
                             sink: SinkType::OpenGLWindow {
                                 full_screen: monitor_config.to_fullscreen_config()?,
+                                canvas: monitor_config.canvas.clone(),
+                                monitor: monitor_config.monitor_match.clone(),
+                                kiosk: monitor_config.kiosk.clone(),
+                                pacing: monitor_config.pacing.clone(),
                             },
                         });
                     }
+                    SinkElementType::File(recording) => {
+                        sinks.push(SinkConfig {
+                            name: name,
+                            id: id,
+                            sink: SinkType::File(recording.clone()),
+                        });
+                    }
+                    SinkElementType::Stream(output) => {
+                        sinks.push(SinkConfig {
+                            name: name,
+                            id: id,
+                            sink: SinkType::Stream(output.clone()),
+                        });
+                    }
+                    SinkElementType::SharedMemory(output) => {
+                        sinks.push(SinkConfig {
+                            name: name,
+                            id: id,
+                            sink: SinkType::SharedMemory(output.clone()),
+                        });
+                    }
+                    SinkElementType::Span(span) => {
+                        sinks.push(SinkConfig {
+                            name: name,
+                            id: id,
+                            sink: SinkType::Span(span.clone()),
+                        });
+                    }
                     _ => {}
                 },
                 ElementData::Source(source_config) => match source_config {
@@ -151,14 +186,101 @@ impl CoreView for &mut SimpleUiCore {
                             id: id,
                             source: SourceType::URI(URI {
                                 uri: config.uri.clone(),
+                                playback: config.playback.clone(),
+                                alpha: config.alpha,
                             }),
+                            audio: config.audio.clone(),
+                            failure: element.failure.clone(),
                         });
                     }
                     SourceElementType::Test(config) => {
                         sources.push(SourceConfig {
                             name: name,
                             id: id,
-                            source: SourceType::Test(Test {}),
+                            source: SourceType::Test(config.test.clone()),
+                            audio: None,
+                            failure: element.failure.clone(),
+                        });
+                    }
+                    SourceElementType::Capture(config) => {
+                        sources.push(SourceConfig {
+                            name: name,
+                            id: id,
+                            source: SourceType::Capture(config.clone()),
+                            audio: None,
+                            failure: element.failure.clone(),
+                        });
+                    }
+                    SourceElementType::Screen(config) => {
+                        sources.push(SourceConfig {
+                            name: name,
+                            id: id,
+                            source: SourceType::Screen(config.clone()),
+                            audio: None,
+                            failure: element.failure.clone(),
+                        });
+                    }
+                    SourceElementType::Pipeline(config) => {
+                        sources.push(SourceConfig {
+                            name: name,
+                            id: id,
+                            source: SourceType::Pipeline(config.clone()),
+                            audio: None,
+                            failure: element.failure.clone(),
+                        });
+                    }
+                    SourceElementType::Color(config) => {
+                        sources.push(SourceConfig {
+                            name: name,
+                            id: id,
+                            source: SourceType::Color(config.clone()),
+                            audio: None,
+                            failure: element.failure.clone(),
+                        });
+                    }
+                    SourceElementType::Text(config) => {
+                        sources.push(SourceConfig {
+                            name: name,
+                            id: id,
+                            source: SourceType::Text(config.clone()),
+                            audio: None,
+                            failure: element.failure.clone(),
+                        });
+                    }
+                    SourceElementType::NetworkStream(config) => {
+                        sources.push(SourceConfig {
+                            name: name,
+                            id: id,
+                            source: SourceType::NetworkStream(config.stream.clone()),
+                            audio: config.audio.clone(),
+                            failure: element.failure.clone(),
+                        });
+                    }
+                    SourceElementType::ImageSequence(config) => {
+                        sources.push(SourceConfig {
+                            name: name,
+                            id: id,
+                            source: SourceType::ImageSequence(config.clone()),
+                            audio: None,
+                            failure: element.failure.clone(),
+                        });
+                    }
+                    SourceElementType::SharedMemory(config) => {
+                        sources.push(SourceConfig {
+                            name: name,
+                            id: id,
+                            source: SourceType::SharedMemory(config.clone()),
+                            audio: None,
+                            failure: element.failure.clone(),
+                        });
+                    }
+                    SourceElementType::Custom(config) => {
+                        sources.push(SourceConfig {
+                            name: name,
+                            id: id,
+                            source: SourceType::Custom(config.clone()),
+                            audio: None,
+                            failure: element.failure.clone(),
                         });
                     }
                     _ => {}
@@ -192,6 +314,7 @@ impl CoreView for &mut SimpleUiCore {
                             region: RegionType::Display {
                                 source: src.id(),
                                 sink: sink.id(),
+                                layer: display.layer,
                             },
                         });
                     }
@@ -219,6 +342,7 @@ impl CoreView for &mut SimpleUiCore {
                 id: source.id,
                 data_type: data.element_type(),
                 data: data,
+                failure: source.failure,
             };
             new_elements.push(ui_data);
         }
@@ -229,6 +353,7 @@ impl CoreView for &mut SimpleUiCore {
                 id: sink.id,
                 data_type: data.element_type(),
                 data: data,
+                failure: FailurePolicy::default(),
             };
             new_elements.push(ui_data);
         }
@@ -239,6 +364,7 @@ impl CoreView for &mut SimpleUiCore {
                 id: region.id,
                 data_type: data.element_type(),
                 data: data,
+                failure: FailurePolicy::default(),
             };
             new_elements.push(ui_data);
         }
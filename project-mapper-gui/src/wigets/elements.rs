@@ -5,17 +5,37 @@ use eframe::egui::{self, Response, Ui, Widget};
 use project_mapper_core::config::{
     options::{RegionType, RegionTypeOptions, SinkTypeOptions, SourceTypeOptions},
     runtime::RegionConfig,
-    sink::{FullScreenMode, MonitorInfo, SinkConfig, SinkType},
-    source::{SourceConfig, SourceType},
+    sink::{
+        FramePacing, FullScreenMode, KioskOptions, MonitorInfo, MonitorMatch, Recording,
+        Resolution, SharedMemoryOutput, SinkConfig, SinkType, Span, StreamOutput, WindowGeometry,
+    },
+    source::{
+        AudioConfig, Capture, Color, Custom, FailurePolicy, ImageSequence, NetworkStream, Pipeline,
+        Playback, Screen, SharedMemory, SourceConfig, SourceType, Test, Text,
+    },
 };
 use rand::distr::Alphanumeric;
 use strum::IntoEnumIterator;
 use strum_macros::Display;
 
-use super::{region::DisplayElementWidget, sink::MonitorElementWidget, source::URIElementWidget};
+use super::{
+    region::DisplayElementWidget,
+    sink::{
+        FileElementWidget, MonitorElementWidget, SharedMemoryOutputElementWidget,
+        SpanElementWidget, StreamElementWidget,
+    },
+    source::{
+        CaptureElementWidget, ColorElementWidget, ImageSequenceElementWidget,
+        NetworkStreamElementWidget, PipelineElementWidget, SharedMemoryElementWidget,
+        TestElementWidget, TextElementWidget, URIElementWidget,
+    },
+};
 use crate::{
     config::{
-        consts::{BORDERLESS_FULLSCREEN_MODE, EXCLUSIVE_FULLSCREEN_MODE, WINDOWED_FULLSCREEN_MODE},
+        consts::{
+            BORDERLESS_FULLSCREEN_MODE, EXCLUSIVE_FULLSCREEN_MODE, OUTPUT_FULLSCREEN_MODE,
+            WINDOWED_FULLSCREEN_MODE,
+        },
         parser::ParsedAvailableConfig,
     },
     core::simple_ui::UiEvent,
@@ -26,12 +46,21 @@ use rand::prelude::*;
 pub struct MonitorElementConfig {
     pub mode: String,
     pub monitor: MonitorInfo,
+    // only used in windowed mode
+    pub geometry: WindowGeometry,
+    // only used in output mode
+    pub output: String,
+    pub canvas: Option<Resolution>,
+    // descriptor of the chosen monitor, so it is found again when its name changes
+    pub monitor_match: Option<MonitorMatch>,
+    pub kiosk: KioskOptions,
+    pub pacing: FramePacing,
 }
 
 impl MonitorElementConfig {
     pub fn to_fullscreen_config(&self) -> Result<FullScreenMode> {
         if self.mode == WINDOWED_FULLSCREEN_MODE {
-            Ok(FullScreenMode::Windowed {})
+            Ok(FullScreenMode::Windowed(self.geometry.clone()))
         } else if self.mode == BORDERLESS_FULLSCREEN_MODE {
             Ok(FullScreenMode::Borderless {
                 name: self.monitor.name.clone(),
@@ -40,19 +69,29 @@ impl MonitorElementConfig {
             Ok(FullScreenMode::Exclusive {
                 info: self.monitor.clone(),
             })
+        } else if self.mode == OUTPUT_FULLSCREEN_MODE {
+            Ok(FullScreenMode::Output {
+                name: self.output.clone(),
+            })
         } else {
             Err(Error::msg(format!("Unknown mode {}", self.mode)))
         }
     }
     pub fn from_fullscreen_config(mode: &FullScreenMode) -> Self {
         match mode {
-            FullScreenMode::Windowed {} => MonitorElementConfig {
+            FullScreenMode::Windowed(geometry) => MonitorElementConfig {
                 mode: WINDOWED_FULLSCREEN_MODE.to_owned(),
                 monitor: MonitorInfo {
                     name: "".to_owned(),
                     resolution: "".to_owned(),
                     refresh_rate_hz: 0,
                 },
+                geometry: geometry.clone(),
+                output: "".to_owned(),
+                canvas: None,
+                monitor_match: None,
+                kiosk: KioskOptions::default(),
+                pacing: FramePacing::default(),
             },
             FullScreenMode::Borderless { name } => MonitorElementConfig {
                 mode: BORDERLESS_FULLSCREEN_MODE.to_owned(),
@@ -61,10 +100,36 @@ impl MonitorElementConfig {
                     resolution: "".to_owned(),
                     refresh_rate_hz: 0,
                 },
+                geometry: WindowGeometry::default(),
+                output: "".to_owned(),
+                canvas: None,
+                monitor_match: None,
+                kiosk: KioskOptions::default(),
+                pacing: FramePacing::default(),
             },
             FullScreenMode::Exclusive { info } => MonitorElementConfig {
                 mode: EXCLUSIVE_FULLSCREEN_MODE.to_owned(),
                 monitor: info.clone(),
+                geometry: WindowGeometry::default(),
+                output: "".to_owned(),
+                canvas: None,
+                monitor_match: None,
+                kiosk: KioskOptions::default(),
+                pacing: FramePacing::default(),
+            },
+            FullScreenMode::Output { name } => MonitorElementConfig {
+                mode: OUTPUT_FULLSCREEN_MODE.to_owned(),
+                monitor: MonitorInfo {
+                    name: "".to_owned(),
+                    resolution: "".to_owned(),
+                    refresh_rate_hz: 0,
+                },
+                geometry: WindowGeometry::default(),
+                output: name.clone(),
+                canvas: None,
+                monitor_match: None,
+                kiosk: KioskOptions::default(),
+                pacing: FramePacing::default(),
             },
         }
     }
@@ -78,6 +143,12 @@ impl Default for MonitorElementConfig {
                 resolution: "".to_owned(),
                 refresh_rate_hz: 0,
             },
+            geometry: WindowGeometry::default(),
+            output: "".to_owned(),
+            canvas: None,
+            monitor_match: None,
+            kiosk: KioskOptions::default(),
+            pacing: FramePacing::default(),
         }
     }
 }
@@ -86,39 +157,72 @@ impl Default for MonitorElementConfig {
 pub enum SinkElementType {
     Empty(),
     Monitor(MonitorElementConfig),
+    File(Recording),
+    Stream(StreamOutput),
+    SharedMemory(SharedMemoryOutput),
+    Span(Span),
 }
 
 #[derive(Clone, Debug)]
-pub struct TestElementConfig {}
+pub struct TestElementConfig {
+    pub test: Test,
+}
 
 impl Default for TestElementConfig {
     fn default() -> Self {
-        Self {}
+        Self {
+            test: Test::default(),
+        }
     }
 }
 
 #[derive(Clone, Debug)]
 pub struct UriElementConfig {
     pub uri: String,
+    pub playback: Playback,
+    pub alpha: bool,
+    pub audio: Option<AudioConfig>,
 }
 
 impl Default for UriElementConfig {
     fn default() -> Self {
-        Self { uri: "".to_owned() }
+        Self {
+            uri: "".to_owned(),
+            playback: Playback::default(),
+            alpha: false,
+            audio: None,
+        }
     }
 }
 
+#[derive(Clone, Debug, Default)]
+pub struct NetworkStreamElementConfig {
+    pub stream: NetworkStream,
+    pub audio: Option<AudioConfig>,
+}
+
 #[derive(Clone, strum_macros::Display, Debug, strum_macros::EnumIter)]
 pub enum SourceElementType {
     Empty(),
     URI(UriElementConfig),
     Test(TestElementConfig),
+    Capture(Capture),
+    Screen(Screen),
+    Pipeline(Pipeline),
+    Color(Color),
+    Text(Text),
+    NetworkStream(NetworkStreamElementConfig),
+    ImageSequence(ImageSequence),
+    SharedMemory(SharedMemory),
+    // kept so configs using runtime extensions survive editing
+    Custom(Custom),
 }
 
 #[derive(Clone, Debug)]
 pub struct DisplayElementConfig {
     pub source: Option<UiElementInfo>,
     pub sink: Option<UiElementInfo>,
+    pub layer: u32,
     pub element_infos: Option<Vec<UiElementInfo>>,
 }
 
@@ -132,6 +236,7 @@ impl Default for DisplayElementConfig {
         DisplayElementConfig {
             source: None,
             sink: None,
+            layer: 0,
             element_infos: None,
         }
     }
@@ -152,43 +257,80 @@ pub enum ElementData {
 impl ElementData {
     pub fn from_source_config(config: &SourceConfig) -> Self {
         let element_default: SourceElementType = match &config.source {
-            SourceType::Test(config) => {
-                let config = TestElementConfig {};
-                SourceElementType::Test(TestElementConfig::from(config))
+            SourceType::Test(test) => {
+                let config = TestElementConfig { test: test.clone() };
+                SourceElementType::Test(config)
             }
             SourceType::URI(uri) => {
                 let config = UriElementConfig {
                     uri: uri.uri.clone(),
+                    playback: uri.playback.clone(),
+                    alpha: uri.alpha,
+                    audio: config.audio.clone(),
                 };
                 SourceElementType::URI(config)
             }
+            SourceType::Capture(capture) => SourceElementType::Capture(capture.clone()),
+            SourceType::Screen(screen) => SourceElementType::Screen(screen.clone()),
+            SourceType::Pipeline(pipeline) => SourceElementType::Pipeline(pipeline.clone()),
+            SourceType::Color(color) => SourceElementType::Color(color.clone()),
+            SourceType::Text(text) => SourceElementType::Text(text.clone()),
+            SourceType::NetworkStream(stream) => {
+                let config = NetworkStreamElementConfig {
+                    stream: stream.clone(),
+                    audio: config.audio.clone(),
+                };
+                SourceElementType::NetworkStream(config)
+            }
+            SourceType::ImageSequence(sequence) => {
+                SourceElementType::ImageSequence(sequence.clone())
+            }
+            SourceType::SharedMemory(shm) => SourceElementType::SharedMemory(shm.clone()),
+            SourceType::Custom(custom) => SourceElementType::Custom(custom.clone()),
         };
         Self::Source(element_default)
     }
     pub fn from_region_config(config: &RegionConfig) -> Self {
         let element_default: RegionElementType = match &config.region {
-            project_mapper_core::config::runtime::RegionType::Display { source, sink } => {
-                RegionElementType::Display(DisplayElementConfig {
-                    source: Some(UiElementInfo::Source {
-                        id: *source,
-                        name: "".to_owned(),
-                    }),
-                    sink: Some(UiElementInfo::Sink {
-                        id: *sink,
-                        name: "".to_owned(),
-                    }),
-                    element_infos: None,
-                })
-            }
+            project_mapper_core::config::runtime::RegionType::Display {
+                source,
+                sink,
+                layer,
+            } => RegionElementType::Display(DisplayElementConfig {
+                source: Some(UiElementInfo::Source {
+                    id: *source,
+                    name: "".to_owned(),
+                }),
+                sink: Some(UiElementInfo::Sink {
+                    id: *sink,
+                    name: "".to_owned(),
+                }),
+                layer: *layer,
+                element_infos: None,
+            }),
         };
         Self::Region(element_default)
     }
     pub fn from_sink_config(config: &SinkConfig) -> Self {
         let element_default: SinkElementType = match &config.sink {
-            SinkType::OpenGLWindow { full_screen } => {
-                let config = MonitorElementConfig::from_fullscreen_config(full_screen);
+            SinkType::OpenGLWindow {
+                full_screen,
+                canvas,
+                monitor,
+                kiosk,
+                pacing,
+            } => {
+                let mut config = MonitorElementConfig::from_fullscreen_config(full_screen);
+                config.canvas = canvas.clone();
+                config.monitor_match = monitor.clone();
+                config.kiosk = kiosk.clone();
+                config.pacing = pacing.clone();
                 SinkElementType::Monitor(config)
             }
+            SinkType::File(recording) => SinkElementType::File(recording.clone()),
+            SinkType::Stream(output) => SinkElementType::Stream(output.clone()),
+            SinkType::SharedMemory(output) => SinkElementType::SharedMemory(output.clone()),
+            SinkType::Span(span) => SinkElementType::Span(span.clone()),
         };
         Self::Sink(element_default)
     }
@@ -232,6 +374,16 @@ impl ElementData {
                     *self = ElementData::Sink(SinkElementType::Monitor(
                         MonitorElementConfig::default(),
                     ));
+                } else if type_name == "File" {
+                    *self = ElementData::Sink(SinkElementType::File(Recording::default()));
+                } else if type_name == "Stream" {
+                    *self = ElementData::Sink(SinkElementType::Stream(StreamOutput::default()));
+                } else if type_name == "SharedMemory" {
+                    *self = ElementData::Sink(SinkElementType::SharedMemory(
+                        SharedMemoryOutput::default(),
+                    ));
+                } else if type_name == "Span" {
+                    *self = ElementData::Sink(SinkElementType::Span(Span::default()));
                 } else {
                     *self = ElementData::Sink(SinkElementType::Empty());
                 }
@@ -243,6 +395,30 @@ impl ElementData {
                 } else if type_name == "URI" {
                     *self =
                         ElementData::Source(SourceElementType::URI(UriElementConfig::default()));
+                } else if type_name == "Capture" {
+                    *self = ElementData::Source(SourceElementType::Capture(Capture::default()));
+                } else if type_name == "Screen" {
+                    *self = ElementData::Source(SourceElementType::Screen(Screen::default()));
+                } else if type_name == "Pipeline" {
+                    *self = ElementData::Source(SourceElementType::Pipeline(Pipeline::default()));
+                } else if type_name == "Color" {
+                    *self = ElementData::Source(SourceElementType::Color(Color::default()));
+                } else if type_name == "Text" {
+                    *self = ElementData::Source(SourceElementType::Text(Text::default()));
+                } else if type_name == "NetworkStream" {
+                    *self = ElementData::Source(SourceElementType::NetworkStream(
+                        NetworkStreamElementConfig::default(),
+                    ));
+                } else if type_name == "ImageSequence" {
+                    *self = ElementData::Source(SourceElementType::ImageSequence(
+                        ImageSequence::default(),
+                    ));
+                } else if type_name == "SharedMemory" {
+                    *self = ElementData::Source(SourceElementType::SharedMemory(
+                        SharedMemory::default(),
+                    ));
+                } else if type_name == "Custom" {
+                    *self = ElementData::Source(SourceElementType::Custom(Custom::default()));
                 } else {
                     *self = ElementData::Source(SourceElementType::Empty());
                 }
@@ -302,6 +478,8 @@ pub struct UiElementData {
     pub id: u32,
     pub data_type: String,
     pub data: ElementData,
+    // only used by sources, kept across type changes like the name
+    pub failure: FailurePolicy,
 }
 
 impl UiElementData {
@@ -411,6 +589,33 @@ impl<'a> Widget for UiElementWidget<'a> {
 
                             ui.add(widget);
                         }
+                        SinkElementType::File(recording) => {
+                            let widget = FileElementWidget::new(self.config.clone(), self.data)
+                                .expect("uh oh");
+
+                            ui.add(widget);
+                        }
+                        SinkElementType::Stream(output) => {
+                            let widget = StreamElementWidget::new(self.config.clone(), self.data)
+                                .expect("uh oh");
+
+                            ui.add(widget);
+                        }
+                        SinkElementType::SharedMemory(output) => {
+                            let widget = SharedMemoryOutputElementWidget::new(
+                                self.config.clone(),
+                                self.data,
+                            )
+                            .expect("uh oh");
+
+                            ui.add(widget);
+                        }
+                        SinkElementType::Span(span) => {
+                            let widget = SpanElementWidget::new(self.config.clone(), self.data)
+                                .expect("uh oh");
+
+                            ui.add(widget);
+                        }
                         _ => {}
                     },
                     ElementData::Source(source_element) => match source_element {
@@ -420,6 +625,57 @@ impl<'a> Widget for UiElementWidget<'a> {
 
                             ui.add(widget);
                         }
+                        SourceElementType::Test(_) => {
+                            let widget = TestElementWidget::new(self.config.clone(), self.data)
+                                .expect("uh oh");
+
+                            ui.add(widget);
+                        }
+                        SourceElementType::Capture(capture_config) => {
+                            let widget = CaptureElementWidget::new(self.config.clone(), self.data)
+                                .expect("uh oh");
+
+                            ui.add(widget);
+                        }
+                        SourceElementType::Pipeline(pipeline_config) => {
+                            let widget = PipelineElementWidget::new(self.config.clone(), self.data)
+                                .expect("uh oh");
+
+                            ui.add(widget);
+                        }
+                        SourceElementType::Color(color_config) => {
+                            let widget = ColorElementWidget::new(self.config.clone(), self.data)
+                                .expect("uh oh");
+
+                            ui.add(widget);
+                        }
+                        SourceElementType::Text(text_config) => {
+                            let widget = TextElementWidget::new(self.config.clone(), self.data)
+                                .expect("uh oh");
+
+                            ui.add(widget);
+                        }
+                        SourceElementType::NetworkStream(stream_config) => {
+                            let widget =
+                                NetworkStreamElementWidget::new(self.config.clone(), self.data)
+                                    .expect("uh oh");
+
+                            ui.add(widget);
+                        }
+                        SourceElementType::ImageSequence(sequence_config) => {
+                            let widget =
+                                ImageSequenceElementWidget::new(self.config.clone(), self.data)
+                                    .expect("uh oh");
+
+                            ui.add(widget);
+                        }
+                        SourceElementType::SharedMemory(shm_config) => {
+                            let widget =
+                                SharedMemoryElementWidget::new(self.config.clone(), self.data)
+                                    .expect("uh oh");
+
+                            ui.add(widget);
+                        }
                         _ => {}
                     },
                     ElementData::Region(region_element) => match region_element {
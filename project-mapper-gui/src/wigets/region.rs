@@ -19,6 +19,7 @@ pub struct DisplayElementWidget<'a> {
     config: ParsedAvailableConfig,
     src_info: &'a mut Option<UiElementInfo>,
     sink_info: &'a mut Option<UiElementInfo>,
+    layer: &'a mut u32,
 }
 
 impl<'a> DisplayElementWidget<'a> {
@@ -35,6 +36,7 @@ impl<'a> DisplayElementWidget<'a> {
                         config: parsed_config,
                         src_info: &mut display.source,
                         sink_info: &mut display.sink,
+                        layer: &mut display.layer,
                     };
                     if let Some(element_infos) = &mut display.element_infos {
                         for info in element_infos {
@@ -143,6 +145,10 @@ impl<'a> Widget for DisplayElementWidget<'a> {
                         }
                     });
                 ui.end_row();
+
+                ui.label("Layer");
+                ui.add(egui::DragValue::new(self.layer));
+                ui.end_row();
             })
             .response
     }
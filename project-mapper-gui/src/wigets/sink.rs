@@ -1,8 +1,15 @@
 use eframe::egui::{self, Response, Ui, Widget};
-use project_mapper_core::config::sink::{MonitorInfo, Resolution, SinkType};
+use project_mapper_core::config::sink::{
+    DropPolicy, FramePacing, FullScreenMode, KioskOptions, MonitorFallback, MonitorInfo,
+    MonitorMatch, OutputProtocol, Recording, RecordingFormat, Resolution, SharedMemoryOutput,
+    SinkType, Span, SpanTile, StreamOutput, WindowGeometry,
+};
 
 use crate::config::{
-    consts::{BORDERLESS_FULLSCREEN_MODE, EXCLUSIVE_FULLSCREEN_MODE, WINDOWED_FULLSCREEN_MODE},
+    consts::{
+        BORDERLESS_FULLSCREEN_MODE, EXCLUSIVE_FULLSCREEN_MODE, OUTPUT_FULLSCREEN_MODE,
+        WINDOWED_FULLSCREEN_MODE,
+    },
     parser::ParsedAvailableConfig,
 };
 
@@ -14,6 +21,12 @@ pub struct MonitorElementWidget<'a> {
     config: ParsedAvailableConfig,
     mode: &'a mut String,
     monitor: &'a mut MonitorInfo,
+    geometry: &'a mut WindowGeometry,
+    output: &'a mut String,
+    canvas: &'a mut Option<Resolution>,
+    monitor_match: &'a mut Option<MonitorMatch>,
+    kiosk: &'a mut KioskOptions,
+    pacing: &'a mut FramePacing,
 }
 
 impl<'a> MonitorElementWidget<'a> {
@@ -28,6 +41,12 @@ impl<'a> MonitorElementWidget<'a> {
                         config: parsed_config,
                         mode: &mut monitor.mode,
                         monitor: &mut monitor.monitor,
+                        geometry: &mut monitor.geometry,
+                        output: &mut monitor.output,
+                        canvas: &mut monitor.canvas,
+                        monitor_match: &mut monitor.monitor_match,
+                        kiosk: &mut monitor.kiosk,
+                        pacing: &mut monitor.pacing,
                     };
                     widget.ensure_good_selection();
                     Ok(widget)
@@ -108,6 +127,25 @@ impl<'a> Widget for MonitorElementWidget<'a> {
                     });
                 ui.end_row();
 
+                let mut set_canvas = self.canvas.is_some();
+                let mut canvas = self.canvas.clone().unwrap_or(Resolution {
+                    width: 1920,
+                    height: 1080,
+                });
+                ui.label("Canvas");
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut set_canvas, "");
+                    ui.add_enabled(set_canvas, egui::DragValue::new(&mut canvas.width));
+                    ui.label("x");
+                    ui.add_enabled(set_canvas, egui::DragValue::new(&mut canvas.height));
+                });
+                *self.canvas = set_canvas.then_some(canvas);
+                ui.end_row();
+
+                kiosk_rows(ui, self.kiosk);
+                pacing_rows(ui, self.pacing);
+
+                let mut selected_monitor = None;
                 if self.mode == EXCLUSIVE_FULLSCREEN_MODE || self.mode == BORDERLESS_FULLSCREEN_MODE
                 {
                     ui.label("Monitor");
@@ -123,6 +161,7 @@ impl<'a> Widget for MonitorElementWidget<'a> {
                             }
                         });
                     ui.end_row();
+                    selected_monitor = Some(monitor.clone());
 
                     if self.mode == EXCLUSIVE_FULLSCREEN_MODE {
                         if let Some(monitor_config) = config.monitors.get(monitor) {
@@ -165,9 +204,458 @@ impl<'a> Widget for MonitorElementWidget<'a> {
                         }
                     }
                 } else if self.mode == WINDOWED_FULLSCREEN_MODE {
-                    /* Maybe do something */
+                    let geometry = self.geometry;
+
+                    ui.label("Monitor");
+                    let monitor_text = geometry.monitor.clone().unwrap_or("Desktop".to_owned());
+                    egui::ComboBox::from_id_salt("Window Monitor")
+                        .selected_text(monitor_text)
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut geometry.monitor, None, "Desktop");
+                            for ava_monitors in config.monitors.keys() {
+                                ui.selectable_value(
+                                    &mut geometry.monitor,
+                                    Some(ava_monitors.clone()),
+                                    ava_monitors.clone(),
+                                );
+                            }
+                        });
+                    ui.end_row();
+
+                    let mut set_position = geometry.position.is_some();
+                    let mut position = geometry.position.clone().unwrap_or_default();
+                    ui.label("Position");
+                    ui.horizontal(|ui| {
+                        ui.checkbox(&mut set_position, "");
+                        ui.add_enabled(set_position, egui::DragValue::new(&mut position.x));
+                        ui.add_enabled(set_position, egui::DragValue::new(&mut position.y));
+                    });
+                    geometry.position = set_position.then_some(position);
+                    ui.end_row();
+
+                    let mut set_size = geometry.size.is_some();
+                    let mut size = geometry.size.clone().unwrap_or(Resolution {
+                        width: 1280,
+                        height: 720,
+                    });
+                    ui.label("Size");
+                    ui.horizontal(|ui| {
+                        ui.checkbox(&mut set_size, "");
+                        ui.add_enabled(set_size, egui::DragValue::new(&mut size.width));
+                        ui.label("x");
+                        ui.add_enabled(set_size, egui::DragValue::new(&mut size.height));
+                    });
+                    geometry.size = set_size.then_some(size);
+                    ui.end_row();
+
+                    ui.label("Decorations");
+                    ui.checkbox(&mut geometry.decorations, "");
+                    ui.end_row();
+
+                    ui.label("Always On Top");
+                    ui.checkbox(&mut geometry.always_on_top, "");
+                    ui.end_row();
+                    selected_monitor = geometry.monitor.clone();
+                } else if self.mode == OUTPUT_FULLSCREEN_MODE {
+                    // the venue profile picks the monitor
+                    ui.label("Output");
+                    ui.text_edit_singleline(self.output);
+                    ui.end_row();
+                }
+
+                // keep the descriptor of the chosen monitor so the runtime can find it by more
+                // than its name
+                let chosen = self
+                    .monitor_match
+                    .as_ref()
+                    .map(|m| m.descriptor.name.clone());
+                if chosen != selected_monitor {
+                    *self.monitor_match = selected_monitor.and_then(|name| {
+                        config
+                            .monitor_descriptors
+                            .iter()
+                            .find(|descriptor| descriptor.name == name)
+                            .map(|descriptor| MonitorMatch::new(descriptor.clone()))
+                    });
+                }
+                if let Some(monitor_match) = self.monitor_match {
+                    ui.label("If Missing");
+                    egui::ComboBox::from_id_salt("Monitor Fallback")
+                        .selected_text(format!("{:?}", monitor_match.fallback))
+                        .show_ui(ui, |ui| {
+                            for fallback in [
+                                MonitorFallback::Fail,
+                                MonitorFallback::Primary,
+                                MonitorFallback::Any,
+                            ] {
+                                let text = format!("{:?}", fallback);
+                                ui.selectable_value(&mut monitor_match.fallback, fallback, text);
+                            }
+                        });
+                    ui.end_row();
                 }
             })
             .response
     }
 }
+
+/// Grid rows for the kiosk options of a window sink
+fn kiosk_rows(ui: &mut Ui, kiosk: &mut KioskOptions) {
+    ui.label("Hide Cursor");
+    ui.checkbox(&mut kiosk.hide_cursor, "");
+    ui.end_row();
+
+    ui.label("Keep On Top");
+    ui.checkbox(&mut kiosk.always_on_top, "");
+    ui.end_row();
+
+    ui.label("Inhibit Screensaver");
+    ui.checkbox(&mut kiosk.inhibit_screensaver, "");
+    ui.end_row();
+}
+
+/// Grid rows for the frame pacing of a window sink
+fn pacing_rows(ui: &mut Ui, pacing: &mut FramePacing) {
+    ui.label("Vsync");
+    ui.horizontal(|ui| {
+        ui.checkbox(&mut pacing.vsync, "");
+        ui.add_enabled(
+            pacing.vsync,
+            egui::DragValue::new(&mut pacing.swap_interval).range(1..=4),
+        );
+    });
+    ui.end_row();
+
+    ui.label("Queued Frames");
+    ui.add(egui::DragValue::new(&mut pacing.queue_depth).range(1..=16));
+    ui.end_row();
+
+    ui.label("When Full");
+    egui::ComboBox::from_id_salt("Drop Policy")
+        .selected_text(format!("{:?}", pacing.drop_policy))
+        .show_ui(ui, |ui| {
+            for policy in [DropPolicy::Block, DropPolicy::DropOldest] {
+                let text = format!("{:?}", policy);
+                ui.selectable_value(&mut pacing.drop_policy, policy, text);
+            }
+        });
+    ui.end_row();
+
+    let mut set_framerate = pacing.framerate.is_some();
+    let mut framerate = pacing.framerate.unwrap_or(60);
+    ui.label("Framerate");
+    ui.horizontal(|ui| {
+        ui.checkbox(&mut set_framerate, "");
+        ui.add_enabled(
+            set_framerate,
+            egui::DragValue::new(&mut framerate).range(1..=240),
+        );
+    });
+    pacing.framerate = set_framerate.then_some(framerate);
+    ui.end_row();
+}
+
+pub struct FileElementWidget<'a> {
+    config: ParsedAvailableConfig,
+    recording: &'a mut Recording,
+}
+
+impl<'a> FileElementWidget<'a> {
+    pub fn new(
+        parsed_config: ParsedAvailableConfig,
+        sink_data: &'a mut UiElementData,
+    ) -> Result<Self> {
+        match &mut sink_data.data {
+            ElementData::Sink(sink_element) => match sink_element {
+                SinkElementType::File(recording) => Ok(Self {
+                    config: parsed_config,
+                    recording: recording,
+                }),
+                _ => Err(Error::msg("Invalid Sink Element Type")),
+            },
+            _ => Err(Error::msg("Incorrect Element Data for Widget")),
+        }
+    }
+}
+
+impl<'a> Widget for FileElementWidget<'a> {
+    fn ui(self, ui: &mut Ui) -> Response {
+        egui::Grid::new("file_grid")
+            .num_columns(2)
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label("Path");
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.recording.path)
+                        .hint_text("/path/to/preview.mp4"),
+                );
+                ui.end_row();
+
+                ui.label("Format");
+                egui::ComboBox::from_id_salt("Format")
+                    .selected_text(format!("{:?}", self.recording.format))
+                    .show_ui(ui, |ui| {
+                        for format in &self.config.recording_formats {
+                            let value = match format.as_str() {
+                                "Matroska" => RecordingFormat::Matroska,
+                                _ => RecordingFormat::Mp4,
+                            };
+                            ui.selectable_value(&mut self.recording.format, value, format);
+                        }
+                    });
+                ui.end_row();
+
+                ui.label("Resolution");
+                ui.horizontal(|ui| {
+                    ui.add(egui::DragValue::new(&mut self.recording.resolution.width));
+                    ui.label("x");
+                    ui.add(egui::DragValue::new(&mut self.recording.resolution.height));
+                });
+                ui.end_row();
+
+                ui.label("Framerate");
+                ui.add(egui::DragValue::new(&mut self.recording.framerate).range(1..=240));
+                ui.end_row();
+            })
+            .response
+    }
+}
+
+pub struct StreamElementWidget<'a> {
+    config: ParsedAvailableConfig,
+    output: &'a mut StreamOutput,
+}
+
+impl<'a> StreamElementWidget<'a> {
+    pub fn new(
+        parsed_config: ParsedAvailableConfig,
+        sink_data: &'a mut UiElementData,
+    ) -> Result<Self> {
+        match &mut sink_data.data {
+            ElementData::Sink(sink_element) => match sink_element {
+                SinkElementType::Stream(output) => Ok(Self {
+                    config: parsed_config,
+                    output: output,
+                }),
+                _ => Err(Error::msg("Invalid Sink Element Type")),
+            },
+            _ => Err(Error::msg("Incorrect Element Data for Widget")),
+        }
+    }
+}
+
+impl<'a> Widget for StreamElementWidget<'a> {
+    fn ui(self, ui: &mut Ui) -> Response {
+        egui::Grid::new("stream_grid")
+            .num_columns(2)
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label("Protocol");
+                egui::ComboBox::from_id_salt("Protocol")
+                    .selected_text(format!("{:?}", self.output.protocol))
+                    .show_ui(ui, |ui| {
+                        for protocol in &self.config.output_protocols {
+                            let value = match protocol.as_str() {
+                                "Srt" => OutputProtocol::Srt,
+                                _ => OutputProtocol::Rtp,
+                            };
+                            ui.selectable_value(&mut self.output.protocol, value, protocol);
+                        }
+                    });
+                ui.end_row();
+
+                let hint = match self.output.protocol {
+                    OutputProtocol::Rtp => "udp://192.168.0.20:5004",
+                    OutputProtocol::Srt => "srt://192.168.0.20:9000",
+                };
+                ui.label("URI");
+                ui.add(egui::TextEdit::singleline(&mut self.output.uri).hint_text(hint));
+                ui.end_row();
+
+                ui.label("Resolution");
+                ui.horizontal(|ui| {
+                    ui.add(egui::DragValue::new(&mut self.output.resolution.width));
+                    ui.label("x");
+                    ui.add(egui::DragValue::new(&mut self.output.resolution.height));
+                });
+                ui.end_row();
+
+                ui.label("Framerate");
+                ui.add(egui::DragValue::new(&mut self.output.framerate).range(1..=240));
+                ui.end_row();
+
+                ui.label("Bitrate (kbps)");
+                ui.add(egui::DragValue::new(&mut self.output.bitrate_kbps).speed(100));
+                ui.end_row();
+
+                if self.output.protocol == OutputProtocol::Srt {
+                    ui.label("Latency (ms)");
+                    ui.add(egui::DragValue::new(&mut self.output.latency_ms));
+                    ui.end_row();
+                }
+            })
+            .response
+    }
+}
+
+pub struct SharedMemoryOutputElementWidget<'a> {
+    config: ParsedAvailableConfig,
+    output: &'a mut SharedMemoryOutput,
+}
+
+impl<'a> SharedMemoryOutputElementWidget<'a> {
+    pub fn new(
+        parsed_config: ParsedAvailableConfig,
+        sink_data: &'a mut UiElementData,
+    ) -> Result<Self> {
+        match &mut sink_data.data {
+            ElementData::Sink(sink_element) => match sink_element {
+                SinkElementType::SharedMemory(output) => Ok(Self {
+                    config: parsed_config,
+                    output: output,
+                }),
+                _ => Err(Error::msg("Invalid Sink Element Type")),
+            },
+            _ => Err(Error::msg("Incorrect Element Data for Widget")),
+        }
+    }
+}
+
+impl<'a> Widget for SharedMemoryOutputElementWidget<'a> {
+    fn ui(self, ui: &mut Ui) -> Response {
+        egui::Grid::new("shm_output_grid")
+            .num_columns(2)
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label("Socket Path");
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.output.socket_path)
+                        .hint_text("/tmp/project-mapper-output"),
+                );
+                ui.end_row();
+
+                ui.label("Resolution");
+                ui.horizontal(|ui| {
+                    ui.add(egui::DragValue::new(&mut self.output.resolution.width));
+                    ui.label("x");
+                    ui.add(egui::DragValue::new(&mut self.output.resolution.height));
+                });
+                ui.end_row();
+
+                ui.label("Framerate");
+                ui.add(egui::DragValue::new(&mut self.output.framerate).range(1..=240));
+                ui.end_row();
+
+                ui.label("Buffered Frames");
+                ui.add(egui::DragValue::new(&mut self.output.buffer_frames).range(1..=32));
+                ui.end_row();
+            })
+            .response
+    }
+}
+
+pub struct SpanElementWidget<'a> {
+    config: ParsedAvailableConfig,
+    span: &'a mut Span,
+}
+
+impl<'a> SpanElementWidget<'a> {
+    pub fn new(
+        parsed_config: ParsedAvailableConfig,
+        sink_data: &'a mut UiElementData,
+    ) -> Result<Self> {
+        match &mut sink_data.data {
+            ElementData::Sink(sink_element) => match sink_element {
+                SinkElementType::Span(span) => Ok(Self {
+                    config: parsed_config,
+                    span: span,
+                }),
+                _ => Err(Error::msg("Invalid Sink Element Type")),
+            },
+            _ => Err(Error::msg("Incorrect Element Data for Widget")),
+        }
+    }
+}
+
+impl<'a> Widget for SpanElementWidget<'a> {
+    fn ui(self, ui: &mut Ui) -> Response {
+        egui::Grid::new("span_grid")
+            .num_columns(2)
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label("Canvas");
+                ui.horizontal(|ui| {
+                    ui.add(egui::DragValue::new(&mut self.span.canvas.width));
+                    ui.label("x");
+                    ui.add(egui::DragValue::new(&mut self.span.canvas.height));
+                });
+                ui.end_row();
+
+                ui.label("Columns");
+                ui.add(egui::DragValue::new(&mut self.span.columns).range(1..=16));
+                ui.end_row();
+
+                ui.label("Bezel");
+                ui.horizontal(|ui| {
+                    ui.add(egui::DragValue::new(&mut self.span.bezel.horizontal));
+                    ui.label("x");
+                    ui.add(egui::DragValue::new(&mut self.span.bezel.vertical));
+                });
+                ui.end_row();
+
+                kiosk_rows(ui, &mut self.span.kiosk);
+                pacing_rows(ui, &mut self.span.pacing);
+
+                // tiles are put borderless on a monitor, other modes are set in the file
+                let mut remove = None;
+                for (index, tile) in self.span.tiles.iter_mut().enumerate() {
+                    ui.label(format!("Tile {}", index + 1));
+                    ui.horizontal(|ui| {
+                        let current = match &tile.full_screen {
+                            FullScreenMode::Borderless { name } => name.clone(),
+                            FullScreenMode::Output { name } => name.clone(),
+                            _ => "".to_owned(),
+                        };
+                        egui::ComboBox::from_id_salt(format!("Span Tile {index}"))
+                            .selected_text(current)
+                            .show_ui(ui, |ui| {
+                                for ava_monitors in self.config.monitors.keys() {
+                                    if ui.selectable_label(false, ava_monitors).clicked() {
+                                        tile.full_screen = FullScreenMode::Borderless {
+                                            name: ava_monitors.clone(),
+                                        };
+                                        tile.monitor = self
+                                            .config
+                                            .monitor_descriptors
+                                            .iter()
+                                            .find(|descriptor| descriptor.name == *ava_monitors)
+                                            .map(|descriptor| {
+                                                MonitorMatch::new(descriptor.clone())
+                                            });
+                                    }
+                                }
+                            });
+                        if ui.button("Remove").clicked() {
+                            remove = Some(index);
+                        }
+                    });
+                    ui.end_row();
+                }
+                if let Some(index) = remove {
+                    self.span.tiles.remove(index);
+                }
+
+                ui.label("");
+                if ui.button("Add Tile").clicked() {
+                    self.span.tiles.push(SpanTile {
+                        full_screen: FullScreenMode::Borderless {
+                            name: "".to_owned(),
+                        },
+                        monitor: None,
+                    });
+                }
+                ui.end_row();
+            })
+            .response
+    }
+}
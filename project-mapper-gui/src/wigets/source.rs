@@ -1,5 +1,11 @@
 use eframe::egui::{self, Response, Ui, Widget};
-use project_mapper_core::config::sink::{MonitorInfo, Resolution, SinkType};
+use project_mapper_core::config::{
+    sink::{MonitorInfo, Resolution, SinkType},
+    source::{
+        AudioConfig, Capture, Color, ImageSequence, NetworkStream, Pipeline, Playback, Rgba,
+        SharedMemory, StreamProtocol, StreamTransport, Test, TestOverlay, Text,
+    },
+};
 
 use crate::config::{
     consts::{BORDERLESS_FULLSCREEN_MODE, EXCLUSIVE_FULLSCREEN_MODE, WINDOWED_FULLSCREEN_MODE},
@@ -13,6 +19,9 @@ use anyhow::{Error, Result};
 pub struct URIElementWidget<'a> {
     config: ParsedAvailableConfig,
     uri: &'a mut String,
+    playback: &'a mut Playback,
+    alpha: &'a mut bool,
+    audio: &'a mut Option<AudioConfig>,
 }
 
 impl<'a> URIElementWidget<'a> {
@@ -26,6 +35,9 @@ impl<'a> URIElementWidget<'a> {
                     let mut widget = Self {
                         config: parsed_config,
                         uri: &mut config.uri,
+                        playback: &mut config.playback,
+                        alpha: &mut config.alpha,
+                        audio: &mut config.audio,
                     };
                     Ok(widget)
                 }
@@ -45,6 +57,610 @@ impl<'a> Widget for URIElementWidget<'a> {
                 ui.label("Source");
                 ui.add(egui::TextEdit::singleline(self.uri).hint_text("URI"));
                 ui.end_row();
+
+                optional_value_edit(ui, "In point (ms)", &mut self.playback.in_point_ms);
+                optional_value_edit(ui, "Out point (ms)", &mut self.playback.out_point_ms);
+
+                ui.label("Start offset (ms)");
+                ui.add(egui::DragValue::new(&mut self.playback.start_offset_ms));
+                ui.end_row();
+
+                ui.label("Rate");
+                ui.add(egui::DragValue::new(&mut self.playback.rate).speed(0.05));
+                ui.end_row();
+
+                ui.label("Alpha");
+                ui.checkbox(self.alpha, "");
+                ui.end_row();
+
+                audio_edit(ui, self.audio);
+            })
+            .response
+    }
+}
+
+fn audio_edit(ui: &mut Ui, audio: &mut Option<AudioConfig>) {
+    let mut play_audio = audio.is_some();
+    ui.label("Audio");
+    ui.checkbox(&mut play_audio, "");
+    ui.end_row();
+    if play_audio != audio.is_some() {
+        *audio = play_audio.then(AudioConfig::default);
+    }
+
+    if let Some(audio) = audio.as_mut() {
+        ui.label("Volume");
+        ui.add(egui::Slider::new(&mut audio.volume, 0.0..=2.0));
+        ui.end_row();
+
+        ui.label("Mute");
+        ui.checkbox(&mut audio.mute, "");
+        ui.end_row();
+
+        ui.label("Offset (ms)");
+        ui.add(egui::DragValue::new(&mut audio.offset_ms));
+        ui.end_row();
+    }
+}
+
+fn optional_value_edit(ui: &mut Ui, label: &str, value: &mut Option<u64>) {
+    let mut enabled = value.is_some();
+    ui.label(label);
+    ui.horizontal(|ui| {
+        ui.checkbox(&mut enabled, "");
+        let mut ms = value.unwrap_or(0);
+        ui.add_enabled(enabled, egui::DragValue::new(&mut ms));
+        *value = enabled.then_some(ms);
+    });
+    ui.end_row();
+}
+
+// unset means the source's own size and rate, which the mixer scales to the region anyway
+fn optional_resolution_edit(ui: &mut Ui, resolution: &mut Option<Resolution>) {
+    let mut enabled = resolution.is_some();
+    let mut size = resolution.clone().unwrap_or(Resolution {
+        width: 1920,
+        height: 1080,
+    });
+    ui.label("Resolution");
+    ui.horizontal(|ui| {
+        ui.checkbox(&mut enabled, "");
+        ui.add_enabled(enabled, egui::DragValue::new(&mut size.width));
+        ui.label("x");
+        ui.add_enabled(enabled, egui::DragValue::new(&mut size.height));
+    });
+    *resolution = enabled.then_some(size);
+    ui.end_row();
+}
+
+fn optional_framerate_edit(ui: &mut Ui, framerate: &mut Option<u32>) {
+    let mut enabled = framerate.is_some();
+    let mut rate = framerate.unwrap_or(30);
+    ui.label("Framerate");
+    ui.horizontal(|ui| {
+        ui.checkbox(&mut enabled, "");
+        ui.add_enabled(enabled, egui::DragValue::new(&mut rate).range(1..=240));
+    });
+    *framerate = enabled.then_some(rate);
+    ui.end_row();
+}
+
+pub struct TestElementWidget<'a> {
+    config: ParsedAvailableConfig,
+    test: &'a mut Test,
+}
+
+impl<'a> TestElementWidget<'a> {
+    pub fn new(
+        parsed_config: ParsedAvailableConfig,
+        source_data: &'a mut UiElementData,
+    ) -> Result<Self> {
+        match &mut source_data.data {
+            ElementData::Source(source_element) => match source_element {
+                SourceElementType::Test(config) => Ok(Self {
+                    config: parsed_config,
+                    test: &mut config.test,
+                }),
+                _ => Err(Error::msg("Invalid Source Element Type")),
+            },
+            _ => Err(Error::msg("Invalid Source Element Type")),
+        }
+    }
+}
+
+impl<'a> Widget for TestElementWidget<'a> {
+    fn ui(self, ui: &mut Ui) -> Response {
+        egui::Grid::new("test_grid")
+            .num_columns(2)
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label("Pattern");
+                let pattern_text = self.test.pattern.clone().unwrap_or("default".to_owned());
+                egui::ComboBox::from_id_salt("Pattern")
+                    .selected_text(pattern_text)
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut self.test.pattern, None, "default");
+                        for pattern in &self.config.test_patterns {
+                            ui.selectable_value(
+                                &mut self.test.pattern,
+                                Some(pattern.clone()),
+                                pattern.clone(),
+                            );
+                        }
+                    });
+                ui.end_row();
+
+                ui.label("Overlay");
+                egui::ComboBox::from_id_salt("Overlay")
+                    .selected_text(format!("{:?}", self.test.overlay))
+                    .show_ui(ui, |ui| {
+                        for (name, overlay) in [
+                            ("None", TestOverlay::None),
+                            ("Timecode", TestOverlay::Timecode),
+                            ("Clock", TestOverlay::Clock),
+                        ] {
+                            if self.config.test_overlays.iter().any(|o| o == name) {
+                                ui.selectable_value(&mut self.test.overlay, overlay, name);
+                            }
+                        }
+                    });
+                ui.end_row();
+
+                optional_resolution_edit(ui, &mut self.test.resolution);
+                optional_framerate_edit(ui, &mut self.test.framerate);
+            })
+            .response
+    }
+}
+
+pub struct CaptureElementWidget<'a> {
+    config: ParsedAvailableConfig,
+    capture: &'a mut Capture,
+}
+
+impl<'a> CaptureElementWidget<'a> {
+    pub fn new(
+        parsed_config: ParsedAvailableConfig,
+        source_data: &'a mut UiElementData,
+    ) -> Result<Self> {
+        match &mut source_data.data {
+            ElementData::Source(source_element) => match source_element {
+                SourceElementType::Capture(config) => Ok(Self {
+                    config: parsed_config,
+                    capture: config,
+                }),
+                _ => Err(Error::msg("Invalid Source Element Type")),
+            },
+            _ => Err(Error::msg("Invalid Source Element Type")),
+        }
+    }
+}
+
+impl<'a> Widget for CaptureElementWidget<'a> {
+    fn ui(self, ui: &mut Ui) -> Response {
+        egui::Grid::new("capture_grid")
+            .num_columns(2)
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label("Device");
+                let current_device = self
+                    .config
+                    .capture_devices
+                    .iter()
+                    .find(|d| d.device == self.capture.device)
+                    .map(|d| d.name.clone())
+                    .unwrap_or(self.capture.device.clone());
+                egui::ComboBox::from_id_salt("Device")
+                    .selected_text(current_device)
+                    .show_ui(ui, |ui| {
+                        for device in &self.config.capture_devices {
+                            ui.selectable_value(
+                                &mut self.capture.device,
+                                device.device.clone(),
+                                device.name.clone(),
+                            );
+                        }
+                    });
+                ui.end_row();
+
+                let mut resolutions: Vec<Resolution> = self
+                    .config
+                    .capture_devices
+                    .iter()
+                    .filter(|d| d.device == self.capture.device)
+                    .flat_map(|d| d.modes.iter().filter_map(|m| m.resolution.clone()))
+                    .collect();
+                resolutions.sort_by(|a, b| b.cmp(a));
+                resolutions.dedup();
+                let resolution_text = self
+                    .capture
+                    .resolution
+                    .as_ref()
+                    .map(|r| r.to_json())
+                    .unwrap_or("default".to_owned());
+
+                ui.label("Resolution");
+                egui::ComboBox::from_id_salt("Resolution")
+                    .selected_text(resolution_text)
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut self.capture.resolution, None, "default");
+                        for resolution in resolutions {
+                            let text = resolution.to_json();
+                            ui.selectable_value(
+                                &mut self.capture.resolution,
+                                Some(resolution),
+                                text,
+                            );
+                        }
+                    });
+                ui.end_row();
+            })
+            .response
+    }
+}
+
+pub struct PipelineElementWidget<'a> {
+    config: ParsedAvailableConfig,
+    description: &'a mut String,
+}
+
+impl<'a> PipelineElementWidget<'a> {
+    pub fn new(
+        parsed_config: ParsedAvailableConfig,
+        source_data: &'a mut UiElementData,
+    ) -> Result<Self> {
+        match &mut source_data.data {
+            ElementData::Source(source_element) => match source_element {
+                SourceElementType::Pipeline(config) => Ok(Self {
+                    config: parsed_config,
+                    description: &mut config.description,
+                }),
+                _ => Err(Error::msg("Invalid Source Element Type")),
+            },
+            _ => Err(Error::msg("Invalid Source Element Type")),
+        }
+    }
+}
+
+impl<'a> Widget for PipelineElementWidget<'a> {
+    fn ui(self, ui: &mut Ui) -> Response {
+        egui::Grid::new("pipeline_grid")
+            .num_columns(2)
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label("Pipeline");
+                ui.add(
+                    egui::TextEdit::multiline(self.description)
+                        .hint_text("videotestsrc pattern=ball"),
+                );
+                ui.end_row();
+            })
+            .response
+    }
+}
+
+fn rgba_edit(ui: &mut Ui, color: &mut Rgba) {
+    let mut rgba = [color.red, color.green, color.blue, color.alpha];
+    if ui.color_edit_button_srgba_unmultiplied(&mut rgba).changed() {
+        let [red, green, blue, alpha] = rgba;
+        *color = Rgba {
+            red,
+            green,
+            blue,
+            alpha,
+        };
+    }
+}
+
+pub struct ColorElementWidget<'a> {
+    config: ParsedAvailableConfig,
+    color: &'a mut Color,
+}
+
+impl<'a> ColorElementWidget<'a> {
+    pub fn new(
+        parsed_config: ParsedAvailableConfig,
+        source_data: &'a mut UiElementData,
+    ) -> Result<Self> {
+        match &mut source_data.data {
+            ElementData::Source(source_element) => match source_element {
+                SourceElementType::Color(config) => Ok(Self {
+                    config: parsed_config,
+                    color: config,
+                }),
+                _ => Err(Error::msg("Invalid Source Element Type")),
+            },
+            _ => Err(Error::msg("Invalid Source Element Type")),
+        }
+    }
+}
+
+impl<'a> Widget for ColorElementWidget<'a> {
+    fn ui(self, ui: &mut Ui) -> Response {
+        egui::Grid::new("color_grid")
+            .num_columns(2)
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label("Color");
+                rgba_edit(ui, &mut self.color.color);
+                ui.end_row();
+
+                optional_resolution_edit(ui, &mut self.color.resolution);
+                optional_framerate_edit(ui, &mut self.color.framerate);
+            })
+            .response
+    }
+}
+
+pub struct TextElementWidget<'a> {
+    config: ParsedAvailableConfig,
+    text: &'a mut Text,
+}
+
+impl<'a> TextElementWidget<'a> {
+    pub fn new(
+        parsed_config: ParsedAvailableConfig,
+        source_data: &'a mut UiElementData,
+    ) -> Result<Self> {
+        match &mut source_data.data {
+            ElementData::Source(source_element) => match source_element {
+                SourceElementType::Text(config) => Ok(Self {
+                    config: parsed_config,
+                    text: config,
+                }),
+                _ => Err(Error::msg("Invalid Source Element Type")),
+            },
+            _ => Err(Error::msg("Invalid Source Element Type")),
+        }
+    }
+}
+
+impl<'a> Widget for TextElementWidget<'a> {
+    fn ui(self, ui: &mut Ui) -> Response {
+        egui::Grid::new("text_grid")
+            .num_columns(2)
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label("Text");
+                ui.add(egui::TextEdit::multiline(&mut self.text.text));
+                ui.end_row();
+
+                let mut size = self.text.size.unwrap_or(24);
+                ui.label("Size");
+                if ui.add(egui::DragValue::new(&mut size)).changed() {
+                    self.text.size = Some(size);
+                }
+                ui.end_row();
+
+                ui.label("Color");
+                rgba_edit(ui, &mut self.text.color);
+                ui.end_row();
+
+                ui.label("Background");
+                rgba_edit(ui, &mut self.text.background);
+                ui.end_row();
+
+                optional_resolution_edit(ui, &mut self.text.resolution);
+                optional_framerate_edit(ui, &mut self.text.framerate);
+            })
+            .response
+    }
+}
+
+pub struct NetworkStreamElementWidget<'a> {
+    config: ParsedAvailableConfig,
+    stream: &'a mut NetworkStream,
+    audio: &'a mut Option<AudioConfig>,
+}
+
+impl<'a> NetworkStreamElementWidget<'a> {
+    pub fn new(
+        parsed_config: ParsedAvailableConfig,
+        source_data: &'a mut UiElementData,
+    ) -> Result<Self> {
+        match &mut source_data.data {
+            ElementData::Source(source_element) => match source_element {
+                SourceElementType::NetworkStream(config) => Ok(Self {
+                    config: parsed_config,
+                    stream: &mut config.stream,
+                    audio: &mut config.audio,
+                }),
+                _ => Err(Error::msg("Invalid Source Element Type")),
+            },
+            _ => Err(Error::msg("Invalid Source Element Type")),
+        }
+    }
+}
+
+impl<'a> Widget for NetworkStreamElementWidget<'a> {
+    fn ui(self, ui: &mut Ui) -> Response {
+        egui::Grid::new("network_stream_grid")
+            .num_columns(2)
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label("Protocol");
+                egui::ComboBox::from_id_salt("Protocol")
+                    .selected_text(format!("{:?}", self.stream.protocol))
+                    .show_ui(ui, |ui| {
+                        for protocol in &self.config.stream_protocols {
+                            let value = match protocol.as_str() {
+                                "Srt" => StreamProtocol::Srt,
+                                "Rtp" => StreamProtocol::Rtp,
+                                _ => StreamProtocol::Rtsp,
+                            };
+                            ui.selectable_value(&mut self.stream.protocol, value, protocol);
+                        }
+                    });
+                ui.end_row();
+
+                let hint = match self.stream.protocol {
+                    StreamProtocol::Rtsp => "rtsp://host:554/stream",
+                    StreamProtocol::Srt => "srt://host:9000",
+                    StreamProtocol::Rtp => "udp://0.0.0.0:5004",
+                };
+                ui.label("URI");
+                ui.add(egui::TextEdit::singleline(&mut self.stream.uri).hint_text(hint));
+                ui.end_row();
+
+                let mut latency = self.stream.latency_ms.unwrap_or(200);
+                ui.label("Latency (ms)");
+                if ui.add(egui::DragValue::new(&mut latency)).changed() {
+                    self.stream.latency_ms = Some(latency);
+                }
+                ui.end_row();
+
+                if self.stream.protocol == StreamProtocol::Rtsp {
+                    ui.label("Transport");
+                    egui::ComboBox::from_id_salt("Transport")
+                        .selected_text(format!("{:?}", self.stream.transport))
+                        .show_ui(ui, |ui| {
+                            for transport in [
+                                StreamTransport::Auto,
+                                StreamTransport::Tcp,
+                                StreamTransport::Udp,
+                            ] {
+                                let text = format!("{:?}", transport);
+                                ui.selectable_value(&mut self.stream.transport, transport, text);
+                            }
+                        });
+                    ui.end_row();
+                }
+
+                if self.stream.protocol == StreamProtocol::Rtp {
+                    let mut caps = self.stream.caps.clone().unwrap_or_default();
+                    ui.label("Caps");
+                    if ui
+                        .add(egui::TextEdit::multiline(&mut caps).hint_text(
+                            "application/x-rtp,media=video,encoding-name=H264,clock-rate=90000",
+                        ))
+                        .changed()
+                    {
+                        self.stream.caps = if caps.is_empty() { None } else { Some(caps) };
+                    }
+                    ui.end_row();
+                }
+
+                audio_edit(ui, self.audio);
+            })
+            .response
+    }
+}
+
+pub struct ImageSequenceElementWidget<'a> {
+    config: ParsedAvailableConfig,
+    sequence: &'a mut ImageSequence,
+}
+
+impl<'a> ImageSequenceElementWidget<'a> {
+    pub fn new(
+        parsed_config: ParsedAvailableConfig,
+        source_data: &'a mut UiElementData,
+    ) -> Result<Self> {
+        match &mut source_data.data {
+            ElementData::Source(source_element) => match source_element {
+                SourceElementType::ImageSequence(config) => Ok(Self {
+                    config: parsed_config,
+                    sequence: config,
+                }),
+                _ => Err(Error::msg("Invalid Source Element Type")),
+            },
+            _ => Err(Error::msg("Invalid Source Element Type")),
+        }
+    }
+}
+
+impl<'a> Widget for ImageSequenceElementWidget<'a> {
+    fn ui(self, ui: &mut Ui) -> Response {
+        egui::Grid::new("image_sequence_grid")
+            .num_columns(2)
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label("Directory");
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.sequence.directory)
+                        .hint_text("/media/frames"),
+                );
+                ui.end_row();
+
+                ui.label("Pattern");
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.sequence.pattern)
+                        .hint_text("frame_%05d.png"),
+                );
+                ui.end_row();
+
+                let mut framerate = self.sequence.framerate.unwrap_or(25);
+                ui.label("Framerate");
+                if ui.add(egui::DragValue::new(&mut framerate)).changed() {
+                    self.sequence.framerate = Some(framerate);
+                }
+                ui.end_row();
+
+                ui.label("Start frame");
+                ui.add(egui::DragValue::new(&mut self.sequence.start_frame));
+                ui.end_row();
+
+                let mut end_frame = self.sequence.end_frame.map(u64::from);
+                optional_value_edit(ui, "End frame", &mut end_frame);
+                self.sequence.end_frame = end_frame.map(|frame| frame as u32);
+
+                ui.label("Loop");
+                ui.checkbox(&mut self.sequence.looping, "");
+                ui.end_row();
+
+                let mut preload = self.sequence.preload_frames.unwrap_or(10);
+                ui.label("Preload frames");
+                if ui.add(egui::DragValue::new(&mut preload)).changed() {
+                    self.sequence.preload_frames = Some(preload);
+                }
+                ui.end_row();
+            })
+            .response
+    }
+}
+
+pub struct SharedMemoryElementWidget<'a> {
+    config: ParsedAvailableConfig,
+    shm: &'a mut SharedMemory,
+}
+
+impl<'a> SharedMemoryElementWidget<'a> {
+    pub fn new(
+        parsed_config: ParsedAvailableConfig,
+        source_data: &'a mut UiElementData,
+    ) -> Result<Self> {
+        match &mut source_data.data {
+            ElementData::Source(source_element) => match source_element {
+                SourceElementType::SharedMemory(config) => Ok(Self {
+                    config: parsed_config,
+                    shm: config,
+                }),
+                _ => Err(Error::msg("Invalid Source Element Type")),
+            },
+            _ => Err(Error::msg("Invalid Source Element Type")),
+        }
+    }
+}
+
+impl<'a> Widget for SharedMemoryElementWidget<'a> {
+    fn ui(self, ui: &mut Ui) -> Response {
+        egui::Grid::new("shared_memory_grid")
+            .num_columns(2)
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label("Socket path");
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.shm.socket_path)
+                        .hint_text("/tmp/project-mapper-shm"),
+                );
+                ui.end_row();
+
+                ui.label("Caps");
+                ui.add(
+                    egui::TextEdit::multiline(&mut self.shm.caps)
+                        .hint_text("video/x-raw,format=RGBA,width=1920,height=1080,framerate=30/1"),
+                );
+                ui.end_row();
             })
             .response
     }
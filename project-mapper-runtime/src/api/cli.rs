@@ -1,22 +1,36 @@
 use std::{
     fs,
     io::{self, Read},
+    time::Duration,
 };
 
 use clap::Parser;
 
+use super::control;
 use crate::runtime;
 use anyhow::Result;
+use project_mapper_core::config::control::{ControlRequest, ControlResponse, SnapshotTarget};
+use project_mapper_core::config::options::AvailableConfig;
+use project_mapper_core::config::venue::VenueProfile;
 
 #[derive(Parser)]
 pub struct Run {
     #[clap(required(true))]
     pub config_path: String,
+    /// Address to accept control requests on e.g. 127.0.0.1:9746
+    #[clap(long)]
+    pub control_address: Option<String>,
+    /// Stop after this many seconds, e.g. to record a clip of a fixed length
+    #[clap(long)]
+    pub duration_s: Option<u64>,
+    /// Venue profile mapping logical outputs to monitors, defaults to venue.json next to the config
+    #[clap(long)]
+    pub venue: Option<String>,
 }
 
 impl Run {
     pub fn run(&self) -> Result<()> {
-        let config = if self.config_path == "-" {
+        let mut config = if self.config_path == "-" {
             println!("attempting to load config from stdin");
             let mut stdin = io::stdin();
             let mut config = String::new();
@@ -27,9 +41,30 @@ impl Run {
 
             project_mapper_core::loader::load_config(&self.config_path)?
         };
-        let mut app = runtime::Runtime::new(config)?;
+        self.venue()?.apply(&mut config)?;
+        let mut app = runtime::Runtime::new(
+            config,
+            self.control_address.clone(),
+            self.duration_s.map(Duration::from_secs),
+        )?;
         app.run()
     }
+
+    fn venue(&self) -> Result<VenueProfile> {
+        if let Some(path) = &self.venue {
+            println!("loading venue profile from '{path}'");
+            return project_mapper_core::loader::load_venue(std::path::Path::new(path));
+        }
+        if self.config_path == "-" {
+            return Ok(VenueProfile::default());
+        }
+        let path = project_mapper_core::loader::venue_path(&self.config_path);
+        if !path.exists() {
+            return Ok(VenueProfile::default());
+        }
+        println!("loading venue profile from '{}'", path.display());
+        project_mapper_core::loader::load_venue(&path)
+    }
 }
 
 #[derive(Parser)]
@@ -51,8 +86,108 @@ impl GetAvailableConfig {
     }
 }
 
+#[derive(Parser)]
+pub struct GenerateVenue {
+    /// Output of get-available-config, the monitors are probed when not given
+    #[clap(short, long)]
+    pub available_config: Option<String>,
+    /// Profile to write, printed when not given
+    #[clap(short, long)]
+    pub output: Option<String>,
+}
+
+impl GenerateVenue {
+    pub fn run(&self) -> Result<()> {
+        let available_config: AvailableConfig = match &self.available_config {
+            Some(path) => serde_json::from_str(&fs::read_to_string(path)?)?,
+            None => runtime::options::generate_options()?,
+        };
+        let venue = VenueProfile::from_available_config(&available_config);
+        let venue_string = serde_json::to_string_pretty(&venue)?;
+        if let Some(path) = &self.output {
+            Ok(fs::write(path, venue_string)?)
+        } else {
+            println!("{}", venue_string);
+            Ok(())
+        }
+    }
+}
+
+#[derive(Parser)]
+pub struct Control {
+    /// Control address of a running runtime
+    #[clap(short, long)]
+    pub address: String,
+    /// JSON encoded control request, read from stdin when "-"
+    #[clap(required(true))]
+    pub request: String,
+}
+
+impl Control {
+    pub fn run(&self) -> Result<()> {
+        let request = if self.request == "-" {
+            let mut request = String::new();
+            io::stdin().read_to_string(&mut request)?;
+            request
+        } else {
+            self.request.clone()
+        };
+        let request: ControlRequest = serde_json::from_str(&request)?;
+
+        let response = control::send_request(&self.address, &request)?;
+        println!("{}", serde_json::to_string(&response)?);
+        match response {
+            ControlResponse::Error { message } => Err(anyhow::Error::msg(message)),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[derive(Parser)]
+pub struct Snapshot {
+    /// Control address of a running runtime
+    #[clap(short, long)]
+    pub address: String,
+    /// Capture what this sink shows
+    #[clap(long, conflicts_with = "source", required_unless_present = "source")]
+    pub sink: Option<u32>,
+    /// Capture the output of this source
+    #[clap(long)]
+    pub source: Option<u32>,
+    /// PNG to write, on the runtime's machine
+    #[clap(required(true))]
+    pub output: String,
+}
+
+impl Snapshot {
+    pub fn run(&self) -> Result<()> {
+        let target = match (self.sink, self.source) {
+            (Some(id), _) => SnapshotTarget::Sink { id: id },
+            (None, Some(id)) => SnapshotTarget::Source { id: id },
+            (None, None) => return Err(anyhow::Error::msg("Pass either --sink or --source")),
+        };
+        // the runtime may run from another directory
+        let path = std::path::absolute(&self.output)?;
+        let request = ControlRequest::Snapshot {
+            target: target,
+            path: path.to_string_lossy().to_string(),
+        };
+
+        match control::send_request(&self.address, &request)? {
+            ControlResponse::Error { message } => Err(anyhow::Error::msg(message)),
+            _ => {
+                println!("saved snapshot to {}", path.display());
+                Ok(())
+            }
+        }
+    }
+}
+
 #[derive(Parser)]
 pub enum Cli {
     Run(Run),
     GetAvailableConfig(GetAvailableConfig),
+    GenerateVenue(GenerateVenue),
+    Control(Control),
+    Snapshot(Snapshot),
 }
@@ -0,0 +1,97 @@
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::{TcpListener, TcpStream},
+    thread,
+};
+
+use anyhow::{Error, Result};
+use project_mapper_core::config::control::{ControlRequest, ControlResponse};
+
+use crate::pipeline::{self, snapshot, source_constructor};
+
+/// Serves `ControlRequest`s for a running pipeline, one JSON object per line
+pub struct ControlServer {
+    pipeline: gst::Pipeline,
+}
+
+impl ControlServer {
+    pub fn new(pipeline: gst::Pipeline) -> ControlServer {
+        ControlServer { pipeline }
+    }
+
+    pub fn start(self, address: &String) -> Result<thread::JoinHandle<()>> {
+        let listener = TcpListener::bind(address)?;
+        println!("listening for control requests on {}", address);
+
+        let control_thread = thread::spawn(move || {
+            for stream in listener.incoming() {
+                match stream {
+                    Ok(stream) => {
+                        if let Err(err) = self.handle_connection(stream) {
+                            eprintln!("Control connection failed: {err}");
+                        }
+                    }
+                    Err(err) => eprintln!("Failed to accept control connection: {err}"),
+                }
+            }
+        });
+        Ok(control_thread)
+    }
+
+    fn handle_connection(&self, stream: TcpStream) -> Result<()> {
+        let mut writer = stream.try_clone()?;
+        for line in BufReader::new(stream).lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let response = match serde_json::from_str::<ControlRequest>(&line) {
+                Ok(request) => self.handle_request(request),
+                Err(err) => ControlResponse::Error {
+                    message: format!("Invalid request: {err}"),
+                },
+            };
+
+            let mut response = serde_json::to_string(&response)?;
+            response.push('\n');
+            writer.write_all(response.as_bytes())?;
+            writer.flush()?;
+        }
+        Ok(())
+    }
+
+    fn handle_request(&self, request: ControlRequest) -> ControlResponse {
+        let result = match request {
+            ControlRequest::UpdateSource { id, source } => {
+                pipeline::MediaPipeline::source_element(&self.pipeline, id)
+                    .ok_or(Error::msg(format!("Unknown source {id}")))
+                    .and_then(|element| Ok(source_constructor::update_element(&source, &element)?))
+            }
+            ControlRequest::Snapshot { target, path } => {
+                snapshot::save_snapshot(&self.pipeline, &target, &path)
+            }
+        };
+
+        match result {
+            Ok(()) => ControlResponse::Ok {},
+            Err(err) => ControlResponse::Error {
+                message: err.to_string(),
+            },
+        }
+    }
+}
+
+/// Sends a single request to a running runtime and waits for its response
+pub fn send_request(address: &String, request: &ControlRequest) -> Result<ControlResponse> {
+    let mut stream = TcpStream::connect(address)?;
+
+    let mut request = serde_json::to_string(request)?;
+    request.push('\n');
+    stream.write_all(request.as_bytes())?;
+    stream.flush()?;
+
+    let mut response = String::new();
+    BufReader::new(stream).read_line(&mut response)?;
+    Ok(serde_json::from_str(&response)?)
+}
@@ -1,2 +1,4 @@
 #[path = "./cli.rs"]
 pub mod cli;
+#[path = "./control.rs"]
+pub mod control;
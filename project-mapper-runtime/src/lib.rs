@@ -0,0 +1,46 @@
+//! Project Mapper runtime. The `project-mapper-runtime` binary only calls `entrypoint`,
+//! applications embedding the runtime can register their own source kinds before doing the same.
+
+use anyhow::Result;
+use api::cli::Cli;
+use clap::Parser;
+use project_mapper_core::config::runtime::RuntimeConfig;
+use std::time::Duration;
+
+#[path = "./runtime/mod.rs"]
+mod runtime;
+
+#[path = "./window/mod.rs"]
+mod window_handler;
+
+#[path = "./render/opengl.rs"]
+mod opengl;
+
+#[path = "./pipeline/pipeline.rs"]
+mod pipeline;
+
+#[path = "./api/mod.rs"]
+mod api;
+
+#[path = "./utils/main_wrapper.rs"]
+pub mod main_wrapper;
+
+pub use pipeline::source_constructor::{SourceKind, SourceTypeConstructor, register_source_kind};
+
+/// Runs `config` in this process the way `run` does, for applications that build their config in
+/// code or need their registered source kinds
+pub fn run_config(config: RuntimeConfig, duration: Option<Duration>) -> Result<()> {
+    runtime::Runtime::new(config, None, duration)?.run()
+}
+
+/// Parses the command line and runs the requested command
+pub fn entrypoint() -> Result<()> {
+    let args = Cli::parse();
+    match &args {
+        Cli::Run(run) => run.run(),
+        Cli::GetAvailableConfig(gac) => gac.run(),
+        Cli::GenerateVenue(venue) => venue.run(),
+        Cli::Control(control) => control.run(),
+        Cli::Snapshot(snapshot) => snapshot.run(),
+    }
+}
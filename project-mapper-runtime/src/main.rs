@@ -10,39 +10,10 @@
 // as the playbin's properties and signals.
 
 use anyhow::Result;
-use api::cli::Cli;
-use clap::Parser;
-use gst_gl::prelude::*;
-
-#[path = "./runtime/mod.rs"]
-mod runtime;
-
-#[path = "./window/mod.rs"]
-mod window_handler;
-
-#[path = "./render/opengl.rs"]
-mod opengl;
-
-#[path = "./pipeline/pipeline.rs"]
-mod pipeline;
-
-#[path = "./api/mod.rs"]
-mod api;
-
-#[path = "./utils/main_wrapper.rs"]
-pub mod main_wrapper;
-
-fn entrypoint() -> Result<()> {
-    let args = Cli::parse();
-    match &args {
-        Cli::Run(run) => run.run(),
-        Cli::GetAvailableConfig(gac) => gac.run(),
-    }
-}
 
 fn main() -> Result<()> {
     // examples_common::run is only required to set up the application environment on macOS
     // (but not necessary in normal Cocoa applications where this is set up automatically)
     //main_wrapper::run(entrypoint)
-    entrypoint()
+    project_mapper_runtime::entrypoint()
 }
@@ -10,19 +10,42 @@ use anyhow::Result;
 use gst::Element;
 use gst_gl::prelude::*;
 
+use crate::opengl;
 use crate::window_handler;
 use crate::window_handler::WindowHandler;
 use project_mapper_core::config;
 
-use std::sync::mpsc;
+use std::sync::{Mutex, mpsc};
+use std::time::Duration;
 
 #[path = "./source_constructor.rs"]
-mod source_constructor;
+pub(crate) mod source_constructor;
+
+#[path = "./recovery.rs"]
+pub(crate) mod recovery;
+
+#[path = "./sink_constructor.rs"]
+pub(crate) mod sink_constructor;
+
+#[path = "./snapshot.rs"]
+pub(crate) mod snapshot;
 
 use project_mapper_core::config::events;
 use project_mapper_core::config::runtime;
 use std::collections::HashMap;
 
+// live sources never preroll so don't wait on them forever
+const PREROLL_TIMEOUT_S: u64 = 5;
+
+// recordings are only finalized once the EOS reaches them
+const EOS_TIMEOUT_S: u64 = 10;
+
+/// The window side of the runtime, missing when no sink needs a window
+pub(crate) type Windows<'a> = Option<(
+    &'a mut WindowHandler,
+    &'a winit::event_loop::EventLoop<window_handler::Message>,
+)>;
+
 pub(crate) struct MediaPipeline {
     pub pipeline: gst::Pipeline,
     runtime_sender: mpsc::Sender<events::RuntimeEvent>,
@@ -33,17 +56,46 @@ pub(crate) struct MediaPipeline {
 impl MediaPipeline {
     pub(crate) fn new(
         config: runtime::RuntimeConfig,
-        window_handler: &mut WindowHandler,
-        event_loop: &winit::event_loop::EventLoop<window_handler::Message>,
+        windows: Windows<'_>,
         runtime_sender: mpsc::Sender<events::RuntimeEvent>,
     ) -> Result<MediaPipeline> {
         gst::init()?;
 
-        let (elements, pipeline) =
-            MediaPipeline::create_pipeline(&config, window_handler, event_loop)?;
+        let event_proxy = windows
+            .as_ref()
+            .map(|(_, event_loop)| Mutex::new(event_loop.create_proxy()));
+        let (elements, source_elements, pipeline) =
+            MediaPipeline::create_pipeline(&config, windows)?;
 
         let pipeline: gst::Pipeline = pipeline.to_owned();
 
+        // source errors are recovered from, everything else goes to the window handler
+        let recovery = recovery::SourceRecovery::new(
+            &config,
+            &source_elements,
+            &pipeline,
+            runtime_sender.clone(),
+        );
+        let bus_sender = runtime_sender.clone();
+        pipeline
+            .bus()
+            .expect("Pipeline without bus")
+            .set_sync_handler(move |_, msg| {
+                if recovery.handle_message(msg) {
+                    return gst::BusSyncReply::Drop;
+                }
+                match &event_proxy {
+                    Some(event_proxy) => {
+                        let _ = event_proxy
+                            .lock()
+                            .unwrap()
+                            .send_event(window_handler::Message::BusMessage(msg.clone()));
+                    }
+                    None => MediaPipeline::handle_headless_message(msg, &bus_sender),
+                }
+                gst::BusSyncReply::Drop
+            });
+
         let media_pipeline: MediaPipeline = MediaPipeline {
             pipeline: pipeline,
             runtime_sender: runtime_sender,
@@ -54,24 +106,145 @@ impl MediaPipeline {
         Ok(media_pipeline)
     }
 
-    pub(crate) fn start(&mut self) {
-        self.pipeline.set_state(gst::State::Playing).unwrap();
+    pub(crate) fn start(&mut self) -> Result<()> {
+        // preroll first so sources can seek before the first frame is shown
+        self.change_state(gst::State::Paused)?;
+        let _ = self
+            .pipeline
+            .state(gst::ClockTime::from_seconds(PREROLL_TIMEOUT_S));
+
+        for source_config in &self.config.sources {
+            let Some(element) = MediaPipeline::source_element(&self.pipeline, source_config.id)
+            else {
+                continue;
+            };
+            if let Err(err) = source_constructor::prepare_element(&source_config.source, &element) {
+                eprintln!("Failed to prepare source {}: {err}", source_config.id);
+            }
+        }
+
+        self.change_state(gst::State::Playing)
+    }
+
+    fn change_state(&self, state: gst::State) -> Result<()> {
+        let Err(err) = self.pipeline.set_state(state) else {
+            return Ok(());
+        };
+        // a source failing during the change gets locked by recovery, so a retry skips it.
+        // anything else failing is not going to work the second time either
+        let source_locked = self
+            .elements
+            .iter()
+            .any(|element| element.is_locked_state());
+        if !source_locked {
+            return Err(anyhow::Error::msg(format!(
+                "Failed to set pipeline to {state:?}: {err}"
+            )));
+        }
+        self.pipeline.set_state(state).map_err(|err| {
+            anyhow::Error::msg(format!("Failed to set pipeline to {state:?}: {err}"))
+        })?;
+        Ok(())
+    }
+
+    /// Finds the element feeding the input of a source
+    pub fn source_element(pipeline: &gst::Pipeline, id: u32) -> Option<gst::Element> {
+        pipeline
+            .by_name(&format!("input-{}", id))?
+            .static_pad("sink")?
+            .peer()?
+            .parent_element()
+    }
+
+    /// Without windows nobody else is watching the bus, so stop once the pipeline ends
+    fn handle_headless_message(
+        msg: &gst::Message,
+        runtime_sender: &mpsc::Sender<events::RuntimeEvent>,
+    ) {
+        use gst::MessageView;
+        match msg.view() {
+            MessageView::Eos(_) => {
+                let _ = runtime_sender.send(events::RuntimeEvent::StopThread());
+            }
+            MessageView::Error(_) => MediaPipeline::report_error(msg, runtime_sender),
+            _ => {}
+        }
+    }
+
+    /// Reports an error no source recovered from, only stops the runtime when it can't go on
+    pub(crate) fn report_error(
+        msg: &gst::Message,
+        runtime_sender: &mpsc::Sender<events::RuntimeEvent>,
+    ) {
+        let gst::MessageView::Error(err) = msg.view() else {
+            return;
+        };
+        let element = msg
+            .src()
+            .map(|s| s.path_string().to_string())
+            .unwrap_or_else(|| "UNKNOWN".to_owned());
+        let _ = runtime_sender.send(events::RuntimeEvent::PipelineError {
+            element: element,
+            message: format!("{} (debug: {:?})", err.error(), err.debug()),
+        });
+        // missing plugins, failed state changes and the like leave nothing to run
+        let error = err.error();
+        if error.is::<gst::CoreError>() || error.is::<gst::LibraryError>() {
+            let _ = runtime_sender.send(events::RuntimeEvent::StopThread());
+        }
     }
 
     pub fn shutdown_pipeline(pipeline: gst::Pipeline) {
+        // watch every file that hasn't ended yet so its muxer can write out the index
+        let (eos_sender, eos_recver) = mpsc::channel();
+        let mut pending = 0;
+        for element in pipeline.iterate_recurse().into_iter().flatten() {
+            if element
+                .factory()
+                .is_none_or(|factory| factory.name() != "filesink")
+            {
+                continue;
+            }
+            let Some(pad) = element.static_pad("sink") else {
+                continue;
+            };
+            if pad.sticky_event::<gst::event::Eos>(0).is_some() {
+                continue;
+            }
+            let eos_sender = eos_sender.clone();
+            pad.add_probe(gst::PadProbeType::EVENT_DOWNSTREAM, move |_, info| {
+                if let Some(gst::PadProbeData::Event(event)) = &info.data {
+                    if event.type_() == gst::EventType::Eos {
+                        let _ = eos_sender.send(());
+                    }
+                }
+                gst::PadProbeReturn::Ok
+            });
+            pending += 1;
+        }
+
         pipeline.send_event(gst::event::Eos::new());
+        for _ in 0..pending {
+            if eos_recver
+                .recv_timeout(Duration::from_secs(EOS_TIMEOUT_S))
+                .is_err()
+            {
+                eprintln!("Timed out waiting for recordings to finish");
+                break;
+            }
+        }
         pipeline.set_state(gst::State::Null).unwrap();
     }
 
     fn create_pipeline(
         config: &runtime::RuntimeConfig,
-        window_handler: &mut window_handler::WindowHandler,
-        event_loop: &winit::event_loop::EventLoop<window_handler::Message>,
-    ) -> Result<(Vec<gst::Element>, gst::Pipeline)> {
+        mut windows: Windows<'_>,
+    ) -> Result<(Vec<gst::Element>, HashMap<u32, Element>, gst::Pipeline)> {
         let pipeline = gst::Pipeline::default();
 
         let mut elements: Vec<gst::Element> = Vec::<gst::Element>::new();
         let mut src_elements: HashMap<u32, Element> = HashMap::new();
+        let mut source_elements: HashMap<u32, Element> = HashMap::new();
         let mut sink_elements: HashMap<u32, Element> = HashMap::new();
 
         // construct sources
@@ -86,28 +259,84 @@ impl MediaPipeline {
             // Add element to pipeline and configure it
             pipeline.add(&src_element)?;
 
+            // Route the source through a selector so its fallback can take over while it is down
+            let input = gst::ElementFactory::make("identity")
+                .name(format!("input-{}", name))
+                .build()?;
+            let selector = gst::ElementFactory::make("input-selector")
+                .name(format!("selector-{}", name))
+                .build()?;
+            let fallback = source_constructor::create_fallback_element(
+                &source_config.failure.fallback,
+                name.clone(),
+            )?;
+
             // Add tee to src element to allow multiple linkages
             let tee_name: String = format!("tee-{}", name);
             let src_tee = gst::ElementFactory::make("tee").name(tee_name).build()?;
-            pipeline.add(&src_tee)?;
+            pipeline.add_many([&input, &selector, &src_tee])?;
+            if let Some(fallback) = &fallback {
+                pipeline.add(fallback)?;
+            }
 
             // Add sync elements before linking
             src_element.sync_state_with_parent()?;
+            input.sync_state_with_parent()?;
+            selector.sync_state_with_parent()?;
             src_tee.sync_state_with_parent()?;
+            if let Some(fallback) = &fallback {
+                fallback.sync_state_with_parent()?;
+            }
+
+            // the source gets sink_0 and the fallback sink_1, see recovery
+            input.link(&selector)?;
+            if let Some(fallback) = &fallback {
+                fallback.link(&selector)?;
+            }
+            let source_pad = input
+                .static_pad("src")
+                .and_then(|pad| pad.peer())
+                .expect("input is linked to the selector");
+            selector.set_property("active-pad", &source_pad);
+            selector.link(&src_tee)?;
 
             // link elements and add mapping for this id to the tee
-            source_constructor::initialize_element(
-                &source_config.source,
-                &src_element,
-                &src_tee,
-                &pipeline,
-            )?;
+            source_constructor::initialize_element(source_config, &src_element, &input, &pipeline)?;
 
             src_elements.insert(id, src_tee.clone());
+            source_elements.insert(id, src_element.clone());
 
             // Add elements to list
             elements.push(src_element);
+            elements.push(input);
+            elements.push(selector);
             elements.push(src_tee);
+            elements.extend(fallback);
+        }
+
+        // a source can fall back to another source once all tees exist
+        for source_config in &config.sources {
+            let config::source::Fallback::Source { id } = source_config.failure.fallback else {
+                continue;
+            };
+            let tee = src_elements.get(&id).ok_or(anyhow::Error::msg(format!(
+                "Fallback source {} of source {} does not exist",
+                id, source_config.id
+            )))?;
+            let selector = pipeline
+                .by_name(&format!("selector-{}", source_config.id))
+                .expect("every source has a selector");
+
+            // leaky so the inactive pad never holds up the other source
+            let queue = gst::ElementFactory::make("queue")
+                .name(format!("fallback-{}", source_config.id))
+                .property_from_str("leaky", "downstream")
+                .build()?;
+            pipeline.add(&queue)?;
+            queue.sync_state_with_parent()?;
+            tee.link(&queue)?;
+            queue.link(&selector)?;
+            elements.push(queue);
         }
 
         // construct sinks
@@ -115,10 +344,21 @@ impl MediaPipeline {
             let id = sink_config.id;
             let mut name = id.to_string();
             let mut sink_element_option: Option<Element> = None;
+            let mut mixer_factory = "glvideomixer";
+            sink_config
+                .sink
+                .validate()
+                .map_err(|err| anyhow::Error::msg(format!("Sink {id}: {err}")))?;
 
             match &sink_config.sink {
-                config::sink::SinkType::OpenGLWindow { full_screen } => {
+                config::sink::SinkType::OpenGLWindow { pacing, .. } => {
                     name = format!("opengl-{}", id);
+                    let Some((window_handler, event_loop)) = windows.as_mut() else {
+                        return Err(anyhow::Error::msg(format!(
+                            "Sink {} needs a window but the runtime is headless",
+                            id
+                        )));
+                    };
 
                     println!("creating opengl window sink {name}");
 
@@ -128,10 +368,8 @@ impl MediaPipeline {
                         .field("texture-target", "2D")
                         .build();
 
-                    let appsink = gst_app::AppSink::builder()
+                    let appsink = pacing_appsink(pacing)
                         .name(name.clone())
-                        .enable_last_sample(true)
-                        .max_buffers(1)
                         .caps(&caps)
                         .build();
 
@@ -146,34 +384,155 @@ impl MediaPipeline {
                         appsink,
                         event_loop,
                         sink_config.sink.clone(),
+                        None,
                     );
 
                     sink_element_option = Some(sink);
                 }
+                config::sink::SinkType::Span(span) => {
+                    name = format!("span-{}", id);
+                    let Some((window_handler, event_loop)) = windows.as_mut() else {
+                        return Err(anyhow::Error::msg(format!(
+                            "Sink {} needs a window but the runtime is headless",
+                            id
+                        )));
+                    };
+                    println!(
+                        "creating span sink {name} across {} monitors",
+                        span.tiles.len()
+                    );
+
+                    // every tile window gets the whole canvas and draws its own part of it
+                    let bin = gst::Bin::builder().name(name.clone()).build();
+                    let tee = gst::ElementFactory::make("tee").build()?;
+                    bin.add(&tee)?;
+                    for (index, tile) in span.tiles.iter().enumerate() {
+                        let tile_name = format!("opengl-{}-{}", id, index);
+                        let caps = gst_video::VideoCapsBuilder::new()
+                            .features([gst_gl::CAPS_FEATURE_MEMORY_GL_MEMORY])
+                            .format(gst_video::VideoFormat::Rgba)
+                            .field("texture-target", "2D")
+                            .build();
+                        let appsink = pacing_appsink(&span.pacing)
+                            .name(tile_name.clone())
+                            .caps(&caps)
+                            .build();
+                        let queue = gst::ElementFactory::make("queue").build()?;
+                        let sink = gst::ElementFactory::make("glsinkbin")
+                            .name(tile_name.clone())
+                            .property("sink", &appsink)
+                            .build()?;
+                        bin.add_many([&queue, &sink])?;
+                        gst::Element::link_many([&tee, &queue, &sink])?;
+
+                        let window_config = config::sink::SinkType::OpenGLWindow {
+                            full_screen: tile.full_screen.clone(),
+                            canvas: None,
+                            monitor: tile.monitor.clone(),
+                            kiosk: span.kiosk.clone(),
+                            pacing: span.pacing.clone(),
+                        };
+                        let crop = opengl::Crop::from_tile(&span.tile_area(index), &span.canvas);
+                        window_handler.add_sink(
+                            glib::GString::from(tile_name),
+                            appsink,
+                            event_loop,
+                            window_config,
+                            Some(crop),
+                        );
+                    }
+                    let sink_pad = tee.static_pad("sink").expect("tee has a sinkpad");
+                    bin.add_pad(&gst::GhostPad::with_target(&sink_pad)?)?;
+
+                    sink_element_option = Some(bin.upcast());
+                }
+                config::sink::SinkType::File(recording) => {
+                    name = format!("file-{}", id);
+
+                    println!("creating file sink {name} recording to {}", recording.path);
+
+                    // mix in software so recording works on machines without a display
+                    mixer_factory = "compositor";
+                    sink_element_option = Some(sink_constructor::create_recording_element(
+                        recording,
+                        name.clone(),
+                    )?);
+                }
+                config::sink::SinkType::Stream(output) => {
+                    name = format!("output-{}", id);
+
+                    println!("creating stream sink {name} sending to {}", output.uri);
+
+                    mixer_factory = "compositor";
+                    sink_element_option = Some(sink_constructor::create_stream_element(
+                        output,
+                        name.clone(),
+                    )?);
+                }
+                config::sink::SinkType::SharedMemory(output) => {
+                    name = format!("shm-output-{}", id);
+
+                    println!(
+                        "creating shared memory sink {name} on {}",
+                        output.socket_path
+                    );
+
+                    mixer_factory = "compositor";
+                    sink_element_option = Some(sink_constructor::create_shared_memory_element(
+                        output,
+                        name.clone(),
+                    )?);
+                }
             }
 
             // for all sinks add a queue to enable parallel processing
             if let Some(sink) = sink_element_option {
+                // regions are layered by a mixer that blends them using their alpha
+                let mixer = gst::ElementFactory::make(mixer_factory)
+                    .name(format!("mixer-{}", id))
+                    .property_from_str("background", "black")
+                    .build()?;
                 let queue_name = format!("queue-{}", name);
                 let queue_sink = gst::ElementFactory::make("queue")
                     .name(queue_name)
                     .build()?;
 
+                // the mixer output is the canvas, whatever size the sources are
+                let mut canvas_caps = gst_video::VideoCapsBuilder::new();
+                if mixer_factory == "glvideomixer" {
+                    canvas_caps = canvas_caps.features([gst_gl::CAPS_FEATURE_MEMORY_GL_MEMORY]);
+                }
+                if let Some(canvas) = sink_config.sink.canvas() {
+                    canvas_caps = canvas_caps
+                        .width(canvas.width as i32)
+                        .height(canvas.height as i32);
+                }
+                if let Some(framerate) = sink_config.sink.pacing().and_then(|p| p.framerate) {
+                    canvas_caps = canvas_caps.framerate(gst::Fraction::new(framerate as i32, 1));
+                }
+                let canvas = gst::ElementFactory::make("capsfilter")
+                    .name(format!("canvas-{}", id))
+                    .property("caps", canvas_caps.build())
+                    .build()?;
+
                 // add to pipeline
-                pipeline.add(&queue_sink)?;
-                pipeline.add(&sink)?;
+                pipeline.add_many([&mixer, &canvas, &queue_sink, &sink])?;
 
                 // Add sync elements before linking
+                mixer.sync_state_with_parent()?;
+                canvas.sync_state_with_parent()?;
                 queue_sink.sync_state_with_parent()?;
                 sink.sync_state_with_parent()?;
 
-                // link elements and add mapping for this id to the tee
-                queue_sink.link(&sink)?;
-                sink_elements.insert(id, queue_sink.clone());
+                // link elements and add mapping for this id to the mixer
+                gst::Element::link_many([&mixer, &canvas, &queue_sink, &sink])?;
+                sink_elements.insert(id, mixer.clone());
 
-                // add both to elements
+                // add all to elements
                 elements.push(sink);
                 elements.push(queue_sink);
+                elements.push(canvas);
+                elements.push(mixer);
             }
         }
 
@@ -185,15 +544,146 @@ impl MediaPipeline {
         // tie the regions together
         for region in &config.regions {
             match region.region {
-                config::runtime::RegionType::Display { source, sink } => {
+                config::runtime::RegionType::Display {
+                    source,
+                    sink,
+                    layer,
+                } => {
                     let src: &Element = src_elements[&source].as_ref();
-                    let sink: &Element = sink_elements[&sink].as_ref();
+                    let mixer: &Element = sink_elements[&sink].as_ref();
 
-                    src.link(sink)?;
+                    // a queue per region so a slow source can't hold up the others on the mixer
+                    let queue = gst::ElementFactory::make("queue")
+                        .name(format!("region-{}", region.id))
+                        .build()?;
+                    pipeline.add(&queue)?;
+                    queue.sync_state_with_parent()?;
+                    src.link(&queue)?;
+
+                    let mixer_pad =
+                        mixer
+                            .request_pad_simple("sink_%u")
+                            .ok_or(anyhow::Error::msg(format!(
+                                "Sink {} can't take another region",
+                                sink
+                            )))?;
+                    mixer_pad.set_property("zorder", layer);
+                    // without their own geometry regions cover the whole canvas
+                    let canvas = config
+                        .sinks
+                        .iter()
+                        .find(|sink_config| sink_config.id == sink)
+                        .and_then(|sink_config| sink_config.sink.canvas());
+                    if let Some(canvas) = canvas {
+                        mixer_pad.set_property("width", canvas.width as i32);
+                        mixer_pad.set_property("height", canvas.height as i32);
+                    }
+                    queue
+                        .static_pad("src")
+                        .expect("queue has no srcpad")
+                        .link(&mixer_pad)?;
+
+                    elements.push(queue);
                 }
             }
         }
 
-        Ok((elements, pipeline))
+        Ok((elements, source_elements, pipeline))
+    }
+}
+
+/// Appsink handing frames to a window, queued and dropped as the sink's pacing asks. Frames wait
+/// in it until the window pulls them, so a full queue blocks upstream or drops the oldest frame
+fn pacing_appsink(pacing: &config::sink::FramePacing) -> gst_app::builders::AppSinkBuilder {
+    gst_app::AppSink::builder()
+        .enable_last_sample(true)
+        .max_buffers(pacing.queue_depth.max(1))
+        .drop(pacing.drop_policy == config::sink::DropPolicy::DropOldest)
+}
+
+#[cfg(test)]
+mod tests {
+    //! Feeds a window's appsink from a live test pattern while nothing pulls from it, the way
+    //! a window that can't keep up would, and checks the pacing decides which frames survive.
+
+    use std::thread;
+    use std::time::Duration;
+
+    use project_mapper_core::config::sink::{DropPolicy, FramePacing};
+
+    use super::*;
+
+    const FRAME: gst::ClockTime = gst::ClockTime::from_mseconds(100);
+
+    /// Runs a 10 fps live pattern into a two frame deep appsink, stalls for half a second and
+    /// returns the timestamps of the first frames pulled afterwards
+    fn stalled_timestamps(drop_policy: DropPolicy) -> Vec<gst::ClockTime> {
+        gst::init().unwrap();
+        let pacing = FramePacing {
+            queue_depth: 2,
+            drop_policy: drop_policy,
+            ..FramePacing::default()
+        };
+        let appsink = pacing_appsink(&pacing).build();
+        let pipeline = gst::Pipeline::new();
+        let source = gst::ElementFactory::make("videotestsrc")
+            .property("is-live", true)
+            .build()
+            .unwrap();
+        let filter = gst::ElementFactory::make("capsfilter")
+            .property(
+                "caps",
+                gst_video::VideoCapsBuilder::new()
+                    .width(16)
+                    .height(16)
+                    .framerate(gst::Fraction::new(10, 1))
+                    .build(),
+            )
+            .build()
+            .unwrap();
+        pipeline
+            .add_many([&source, &filter, appsink.upcast_ref()])
+            .unwrap();
+        gst::Element::link_many([&source, &filter, appsink.upcast_ref()]).unwrap();
+        pipeline.set_state(gst::State::Playing).unwrap();
+
+        // the window falls behind for five frames
+        thread::sleep(Duration::from_millis(500));
+        let timestamps = (0..3)
+            .map(|_| {
+                appsink
+                    .try_pull_sample(gst::ClockTime::from_seconds(5))
+                    .expect("no frame arrived")
+                    .buffer()
+                    .and_then(|buffer| buffer.pts())
+                    .unwrap()
+            })
+            .collect();
+
+        pipeline.set_state(gst::State::Null).unwrap();
+        timestamps
+    }
+
+    #[test]
+    fn block_keeps_every_frame() {
+        let timestamps = stalled_timestamps(DropPolicy::Block);
+        // the source waited for the window instead of skipping ahead
+        assert!(
+            timestamps[0] < FRAME,
+            "expected the first frame, got {timestamps:?}"
+        );
+        for pair in timestamps.windows(2) {
+            assert_eq!(pair[1] - pair[0], FRAME, "frames were lost: {timestamps:?}");
+        }
+    }
+
+    #[test]
+    fn drop_oldest_skips_to_recent_frames() {
+        let timestamps = stalled_timestamps(DropPolicy::DropOldest);
+        // only the last two frames of the stall are still queued
+        assert!(
+            timestamps[0] >= gst::ClockTime::from_mseconds(300),
+            "expected the stalled frames to be dropped, got {timestamps:?}"
+        );
     }
 }
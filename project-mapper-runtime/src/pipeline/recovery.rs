@@ -0,0 +1,241 @@
+//! Per source failure handling. An error posted by a source switches its selector to the
+//! fallback, the source is then restarted with an exponential backoff until data flows again.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{Arc, mpsc};
+use std::thread;
+use std::time::Duration;
+
+use gst::prelude::*;
+use project_mapper_core::config::{
+    events::RuntimeEvent,
+    runtime::RuntimeConfig,
+    source::{FailurePolicy, SourceType},
+};
+
+use crate::pipeline::source_constructor;
+
+// how long a restarted source gets to preroll before its in point and rate are applied
+const PREROLL_TIMEOUT_S: u64 = 5;
+
+struct SourceState {
+    policy: FailurePolicy,
+    // needed to prepare the element again after a restart
+    source: SourceType,
+    element: glib::WeakRef<gst::Element>,
+    // the fallback is showing
+    failed: AtomicBool,
+    // set by a restart so the next buffer counts as a recovery
+    restarted: AtomicBool,
+    retry_pending: AtomicBool,
+    attempts: AtomicU32,
+}
+
+pub(crate) struct SourceRecovery {
+    sources: HashMap<u32, SourceState>,
+    pipeline: glib::WeakRef<gst::Pipeline>,
+    event_sender: mpsc::Sender<RuntimeEvent>,
+}
+
+impl SourceRecovery {
+    pub(crate) fn new(
+        config: &RuntimeConfig,
+        source_elements: &HashMap<u32, gst::Element>,
+        pipeline: &gst::Pipeline,
+        event_sender: mpsc::Sender<RuntimeEvent>,
+    ) -> Arc<SourceRecovery> {
+        let mut sources = HashMap::new();
+        for source_config in &config.sources {
+            let Some(element) = source_elements.get(&source_config.id) else {
+                continue;
+            };
+            sources.insert(
+                source_config.id,
+                SourceState {
+                    policy: source_config.failure.clone(),
+                    source: source_config.source.clone(),
+                    element: element.downgrade(),
+                    failed: AtomicBool::new(false),
+                    restarted: AtomicBool::new(false),
+                    retry_pending: AtomicBool::new(false),
+                    attempts: AtomicU32::new(0),
+                },
+            );
+        }
+
+        let recovery = Arc::new(SourceRecovery {
+            sources: sources,
+            pipeline: pipeline.downgrade(),
+            event_sender: event_sender,
+        });
+
+        // data reaching the input of a failed source means the restart worked
+        for id in recovery.sources.keys().copied() {
+            let Some(pad) = pipeline
+                .by_name(&format!("input-{}", id))
+                .and_then(|input| input.static_pad("src"))
+            else {
+                continue;
+            };
+            let recovery_weak = Arc::downgrade(&recovery);
+            pad.add_probe(gst::PadProbeType::BUFFER, move |_, _| {
+                if let Some(recovery) = recovery_weak.upgrade() {
+                    recovery.recovered(id);
+                }
+                gst::PadProbeReturn::Ok
+            });
+        }
+
+        recovery
+    }
+
+    /// Takes over error messages posted by a source, returns false for anything else
+    pub(crate) fn handle_message(self: &Arc<Self>, msg: &gst::Message) -> bool {
+        let gst::MessageView::Error(err) = msg.view() else {
+            return false;
+        };
+        let Some(src) = msg.src() else {
+            return false;
+        };
+
+        let id = self.sources.iter().find_map(|(id, source)| {
+            let element = source.element.upgrade()?;
+            let element = element.upcast_ref::<gst::Object>();
+            (src == element || src.has_as_ancestor(element)).then_some(*id)
+        });
+        let Some(id) = id else {
+            return false;
+        };
+
+        self.failed(id, err.error().to_string());
+        true
+    }
+
+    fn failed(self: &Arc<Self>, id: u32, message: String) {
+        let source = &self.sources[&id];
+        source.restarted.store(false, Ordering::SeqCst);
+        // keep the pipeline's state changes away from the broken source until it is restarted
+        if let Some(element) = source.element.upgrade() {
+            element.set_locked_state(true);
+        }
+        if !source.failed.swap(true, Ordering::SeqCst) {
+            self.select(id, true);
+            self.send(RuntimeEvent::SourceFailed {
+                id: id,
+                message: message,
+            });
+        }
+
+        // a failing source usually posts several errors, only schedule one retry
+        if source.retry_pending.swap(true, Ordering::SeqCst) {
+            return;
+        }
+
+        let attempt = source.attempts.fetch_add(1, Ordering::SeqCst) + 1;
+        let policy = &source.policy;
+        if policy.max_retries.is_some_and(|max| attempt > max) {
+            // leave the retry pending so the fallback stays up for good
+            self.send(RuntimeEvent::SourceGaveUp {
+                id: id,
+                attempts: attempt - 1,
+            });
+            return;
+        }
+
+        let delay_ms = policy
+            .retry_delay_ms
+            .saturating_mul(1 << (attempt - 1).min(16))
+            .min(policy.max_retry_delay_ms);
+        self.send(RuntimeEvent::SourceRetrying {
+            id: id,
+            attempt: attempt,
+            delay_ms: delay_ms,
+        });
+
+        let recovery = self.clone();
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(delay_ms));
+            recovery.restart(id);
+        });
+    }
+
+    fn restart(self: &Arc<Self>, id: u32) {
+        let source = &self.sources[&id];
+        let (Some(pipeline), Some(element)) = (self.pipeline.upgrade(), source.element.upgrade())
+        else {
+            return;
+        };
+
+        source.retry_pending.store(false, Ordering::SeqCst);
+        source.restarted.store(true, Ordering::SeqCst);
+
+        let _ = element.set_state(gst::State::Null);
+        let live = match element.set_state(gst::State::Paused) {
+            Ok(success) => success == gst::StateChangeSuccess::NoPreroll,
+            Err(err) => {
+                self.failed(id, err.to_string());
+                return;
+            }
+        };
+
+        // the restart dropped the seek done by prepare, so wait for the preroll and seek again
+        if !live {
+            let _ = element.state(gst::ClockTime::from_seconds(PREROLL_TIMEOUT_S));
+        }
+        if let Err(err) = source_constructor::prepare_element(&source.source, &element) {
+            eprintln!("Failed to prepare source {id} after restart: {err}");
+        }
+
+        // non-live sources start their timestamps at zero again so move them to the current
+        // running time, live sources already timestamp with the pipeline clock
+        let offset = match (live, pipeline.current_running_time()) {
+            (false, Some(running_time)) => running_time.nseconds() as i64,
+            _ => 0,
+        };
+        if let Some(pad) = pipeline
+            .by_name(&format!("input-{}", id))
+            .and_then(|input| input.static_pad("src"))
+        {
+            pad.set_offset(offset);
+        }
+
+        element.set_locked_state(false);
+        if let Err(err) = element.sync_state_with_parent() {
+            self.failed(id, err.to_string());
+        }
+    }
+
+    fn recovered(&self, id: u32) {
+        let source = &self.sources[&id];
+        if !source.failed.load(Ordering::SeqCst) || !source.restarted.load(Ordering::SeqCst) {
+            return;
+        }
+        if !source.failed.swap(false, Ordering::SeqCst) {
+            return;
+        }
+
+        source.attempts.store(0, Ordering::SeqCst);
+        self.select(id, false);
+        self.send(RuntimeEvent::SourceRecovered { id: id });
+    }
+
+    /// Switches the selector of a source between the source and its fallback
+    fn select(&self, id: u32, fallback: bool) {
+        let Some(selector) = self
+            .pipeline
+            .upgrade()
+            .and_then(|pipeline| pipeline.by_name(&format!("selector-{}", id)))
+        else {
+            return;
+        };
+        let pad_name = if fallback { "sink_1" } else { "sink_0" };
+        if let Some(pad) = selector.static_pad(pad_name) {
+            selector.set_property("active-pad", &pad);
+        }
+    }
+
+    fn send(&self, event: RuntimeEvent) {
+        let _ = self.event_sender.send(event);
+    }
+}
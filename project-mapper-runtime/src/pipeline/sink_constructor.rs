@@ -0,0 +1,267 @@
+//! Sinks that don't render to a window. Each is a bin with a single video sink pad that takes the
+//! output of the sink's mixer.
+
+use anyhow::{Error, Result};
+use gst::prelude::*;
+use project_mapper_core::config::sink::{
+    OutputProtocol, Recording, RecordingFormat, Resolution, SharedMemoryOutput, StreamOutput,
+};
+
+// how often an MP4 recording writes its index, so a killed runtime still leaves a playable file
+const MP4_FRAGMENT_DURATION_MS: u32 = 1000;
+
+/// Elements needed for each recording format, the encoder comes first
+fn recording_factories(format: &RecordingFormat) -> &'static [&'static str] {
+    match format {
+        RecordingFormat::Mp4 => &["x264enc", "h264parse", "mp4mux"],
+        RecordingFormat::Matroska => &["avenc_ffv1", "matroskamux"],
+    }
+}
+
+/// Formats that can be recorded with the installed plugins
+pub(crate) fn recording_formats() -> Vec<String> {
+    [RecordingFormat::Mp4, RecordingFormat::Matroska]
+        .iter()
+        .filter(|format| {
+            recording_factories(format)
+                .iter()
+                .all(|factory| gst::ElementFactory::find(factory).is_some())
+        })
+        .map(|format| format!("{:?}", format))
+        .collect()
+}
+
+/// Scales, retimes and encodes the mixer output to `recording.path`
+pub(crate) fn create_recording_element(
+    recording: &Recording,
+    name: String,
+) -> Result<gst::Element> {
+    if recording.path.is_empty() {
+        return Err(Error::msg(format!("Recording {name} has no path")));
+    }
+
+    // x264enc picks a 4:4:4 profile for RGB input, which most players can't decode
+    let format = match recording.format {
+        RecordingFormat::Mp4 => Some(gst_video::VideoFormat::I420),
+        RecordingFormat::Matroska => None,
+    };
+    let mut elements = conform_elements(&name, &recording.resolution, recording.framerate, format)?;
+    for factory in recording_factories(&recording.format) {
+        let element = gst::ElementFactory::make(factory).build().map_err(|_| {
+            Error::msg(format!(
+                "Recording as {:?} needs the {factory} element",
+                recording.format
+            ))
+        })?;
+        if *factory == "mp4mux" {
+            element.set_property("fragment-duration", MP4_FRAGMENT_DURATION_MS);
+        }
+        elements.push(element);
+    }
+    // non-live sources let the mixer run faster than real time, syncing to the clock keeps a
+    // recording stopped by --duration-s that long
+    let filesink = gst::ElementFactory::make("filesink")
+        .property("location", &recording.path)
+        .property("sync", true)
+        .build()?;
+    elements.push(filesink);
+
+    create_bin(name, &elements)
+}
+
+/// Elements needed for each output protocol after the H.264 parser
+fn output_factories(protocol: &OutputProtocol) -> &'static [&'static str] {
+    match protocol {
+        OutputProtocol::Rtp => &["rtph264pay", "udpsink"],
+        OutputProtocol::Srt => &["mpegtsmux", "srtsink"],
+    }
+}
+
+/// Protocols that can be streamed with the installed plugins
+pub(crate) fn output_protocols() -> Vec<String> {
+    [OutputProtocol::Rtp, OutputProtocol::Srt]
+        .iter()
+        .filter(|protocol| {
+            ["x264enc", "h264parse"]
+                .iter()
+                .chain(output_factories(protocol))
+                .all(|factory| gst::ElementFactory::find(factory).is_some())
+        })
+        .map(|protocol| format!("{:?}", protocol))
+        .collect()
+}
+
+/// Encodes the mixer output with low latency settings and sends it to `output.uri`
+pub(crate) fn create_stream_element(output: &StreamOutput, name: String) -> Result<gst::Element> {
+    if output.uri.is_empty() {
+        return Err(Error::msg(format!("Stream {name} has no uri")));
+    }
+
+    // receivers only decode 4:2:0 reliably, and only the profiles below high 4:4:4
+    let mut elements = conform_elements(
+        &name,
+        &output.resolution,
+        output.framerate,
+        Some(gst_video::VideoFormat::I420),
+    )?;
+
+    // a keyframe every second so receivers can join at any time
+    let encoder = gst::ElementFactory::make("x264enc")
+        .property("bitrate", output.bitrate_kbps)
+        .property("key-int-max", output.framerate)
+        .property_from_str("tune", "zerolatency")
+        .property_from_str("speed-preset", "veryfast")
+        .build()
+        .map_err(|_| Error::msg("Streaming needs the x264enc element"))?;
+    let profile = gst::ElementFactory::make("capsfilter")
+        .property(
+            "caps",
+            gst::Caps::builder("video/x-h264")
+                .field("profile", "main")
+                .build(),
+        )
+        .build()?;
+    let parse = gst::ElementFactory::make("h264parse")
+        .property("config-interval", -1i32)
+        .build()?;
+    elements.extend([encoder, profile, parse]);
+
+    for factory in output_factories(&output.protocol) {
+        let element = gst::ElementFactory::make(factory).build().map_err(|_| {
+            Error::msg(format!(
+                "Streaming over {:?} needs the {factory} element",
+                output.protocol
+            ))
+        })?;
+        match *factory {
+            "rtph264pay" => element.set_property("config-interval", -1i32),
+            "srtsink" => element.set_property("latency", output.latency_ms as i32),
+            _ => {}
+        }
+        elements.push(element);
+    }
+
+    // both network sinks take their destination as a uri
+    let network_sink = elements.last().expect("a network sink was added");
+    network_sink
+        .dynamic_cast_ref::<gst::URIHandler>()
+        .expect("network sinks handle uris")
+        .set_uri(&output.uri)
+        .map_err(|err| Error::msg(format!("Invalid stream uri {}: {err}", output.uri)))?;
+
+    create_bin(name, &elements)
+}
+
+/// Publishes raw frames with the caps documented on `SharedMemoryOutput`
+pub(crate) fn create_shared_memory_element(
+    output: &SharedMemoryOutput,
+    name: String,
+) -> Result<gst::Element> {
+    if output.socket_path.is_empty() {
+        return Err(Error::msg(format!(
+            "Shared memory sink {name} has no socket path"
+        )));
+    }
+
+    let mut elements = conform_elements(
+        &name,
+        &output.resolution,
+        output.framerate,
+        Some(gst_video::VideoFormat::Rgba),
+    )?;
+
+    // shmsink blocks while the area is full, drop frames here instead of holding up the mixer
+    let queue = gst::ElementFactory::make("queue")
+        .property_from_str("leaky", "downstream")
+        .property("max-size-buffers", 1u32)
+        .build()?;
+    elements.push(queue);
+
+    let frame_size = output.resolution.width as u64 * output.resolution.height as u64 * 4;
+    let shm_size = frame_size
+        .checked_mul(output.buffer_frames.max(1) as u64)
+        .and_then(|size| u32::try_from(size).ok())
+        .ok_or_else(|| {
+            Error::msg(format!(
+                "Shared memory sink {name} needs more than 4 GiB, lower its resolution or buffer frames"
+            ))
+        })?;
+    // readers that connect late pick up the stream as is, frames are sent in real time
+    let shmsink = gst::ElementFactory::make("shmsink")
+        .property("socket-path", &output.socket_path)
+        .property("shm-size", shm_size)
+        .property("wait-for-connection", false)
+        .property("sync", true)
+        .build()
+        .map_err(|_| Error::msg("Shared memory output needs the shmsink element"))?;
+    elements.push(shmsink);
+
+    create_bin(name, &elements)
+}
+
+/// Converts whatever the mixer produces to a fixed resolution and framerate
+fn conform_elements(
+    name: &str,
+    resolution: &Resolution,
+    framerate: u32,
+    format: Option<gst_video::VideoFormat>,
+) -> Result<Vec<gst::Element>> {
+    if resolution.width == 0 || resolution.height == 0 || framerate == 0 {
+        return Err(Error::msg(format!(
+            "Sink {name} needs a resolution and framerate"
+        )));
+    }
+
+    let convert = gst::ElementFactory::make("videoconvert").build()?;
+    let scale = gst::ElementFactory::make("videoscale").build()?;
+    let rate = gst::ElementFactory::make("videorate").build()?;
+    let mut caps = gst_video::VideoCapsBuilder::new()
+        .width(resolution.width as i32)
+        .height(resolution.height as i32)
+        .framerate(gst::Fraction::new(framerate as i32, 1))
+        .pixel_aspect_ratio(gst::Fraction::new(1, 1));
+    if let Some(format) = format {
+        caps = caps.format(format);
+    }
+    let caps = caps.build();
+    let capsfilter = gst::ElementFactory::make("capsfilter")
+        .property("caps", &caps)
+        .build()?;
+
+    Ok(vec![convert, scale, rate, capsfilter])
+}
+
+/// Links `elements` in a bin that takes video on the first one
+fn create_bin(name: String, elements: &[gst::Element]) -> Result<gst::Element> {
+    let bin = gst::Bin::builder().name(name).build();
+    bin.add_many(elements)?;
+    gst::Element::link_many(elements)?;
+
+    let sink_pad = elements[0]
+        .static_pad("sink")
+        .expect("videoconvert has a sinkpad");
+    bin.add_pad(&gst::GhostPad::with_target(&sink_pad)?)?;
+
+    Ok(bin.upcast())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn oversized_shared_memory_is_rejected() {
+        gst::init().unwrap();
+        let output = SharedMemoryOutput {
+            socket_path: "/tmp/project-mapper-oversized".to_owned(),
+            resolution: Resolution {
+                width: 3840,
+                height: 2160,
+            },
+            framerate: 30,
+            buffer_frames: 200,
+        };
+        let err = create_shared_memory_element(&output, "shm".to_owned()).unwrap_err();
+        assert!(err.to_string().contains("4 GiB"));
+    }
+}
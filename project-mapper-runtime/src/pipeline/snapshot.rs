@@ -0,0 +1,113 @@
+//! Captures the current frame of a sink or source and encodes it as a PNG.
+
+use std::sync::mpsc;
+use std::time::Duration;
+
+use anyhow::{Error, Result};
+use gst::prelude::*;
+use project_mapper_core::config::control::SnapshotTarget;
+
+// a paused or stalled branch never produces another buffer
+const SNAPSHOT_TIMEOUT_S: u64 = 5;
+
+/// Writes the current frame of `target` to `path` as a PNG
+pub(crate) fn save_snapshot(
+    pipeline: &gst::Pipeline,
+    target: &SnapshotTarget,
+    path: &str,
+) -> Result<()> {
+    let png = snapshot(pipeline, target)?;
+    std::fs::write(path, png)?;
+    Ok(())
+}
+
+/// Returns the current frame of `target` encoded as a PNG
+pub(crate) fn snapshot(pipeline: &gst::Pipeline, target: &SnapshotTarget) -> Result<Vec<u8>> {
+    let sample = match target {
+        SnapshotTarget::Sink { id } => match last_shown_sample(pipeline, *id) {
+            Some(sample) => sample,
+            None => {
+                let mixer = pipeline
+                    .by_name(&format!("mixer-{}", id))
+                    .ok_or(Error::msg(format!("Unknown sink {id}")))?;
+                next_sample(&mixer.static_pad("src").expect("mixer has a srcpad"))?
+            }
+        },
+        SnapshotTarget::Source { id } => {
+            let tee = pipeline
+                .by_name(&format!("tee-{}", id))
+                .ok_or(Error::msg(format!("Unknown source {id}")))?;
+            next_sample(&tee.static_pad("sink").expect("tee has a sinkpad"))?
+        }
+    };
+
+    let sample = system_memory_sample(&sample)?;
+    let png_caps = gst::Caps::builder("image/png").build();
+    let png = gst_video::convert_sample(
+        &sample,
+        &png_caps,
+        gst::ClockTime::from_seconds(SNAPSHOT_TIMEOUT_S),
+    )?;
+    let buffer = png
+        .buffer()
+        .ok_or(Error::msg("Encoding the snapshot produced no data"))?;
+    Ok(buffer.map_readable()?.to_vec())
+}
+
+/// The frame a window sink drew last, its appsink keeps it around for us
+fn last_shown_sample(pipeline: &gst::Pipeline, id: u32) -> Option<gst::Sample> {
+    // the glsinkbin and its appsink share a name
+    let element = pipeline.by_name(&format!("opengl-{}", id))?;
+    let appsink = match element.downcast::<gst_app::AppSink>() {
+        Ok(appsink) => appsink,
+        Err(element) => element
+            .property::<Option<gst::Element>>("sink")?
+            .downcast::<gst_app::AppSink>()
+            .ok()?,
+    };
+    appsink.last_sample()
+}
+
+/// Waits for the next buffer passing `pad`
+fn next_sample(pad: &gst::Pad) -> Result<gst::Sample> {
+    let (sender, recver) = mpsc::channel();
+    pad.add_probe(gst::PadProbeType::BUFFER, move |pad, info| {
+        let Some(buffer) = info.buffer().map(|buffer| buffer.to_owned()) else {
+            return gst::PadProbeReturn::Ok;
+        };
+        let mut sample = gst::Sample::builder().buffer(&buffer);
+        let caps = pad.current_caps();
+        if let Some(caps) = &caps {
+            sample = sample.caps(caps);
+        }
+        let _ = sender.send(sample.build());
+        gst::PadProbeReturn::Remove
+    });
+
+    recver
+        .recv_timeout(Duration::from_secs(SNAPSHOT_TIMEOUT_S))
+        .map_err(|_| Error::msg("No frame arrived in time, is the pipeline playing?"))
+}
+
+/// Copies the frame into plain system memory, GL frames can't be encoded directly
+fn system_memory_sample(sample: &gst::Sample) -> Result<gst::Sample> {
+    let caps = sample
+        .caps()
+        .ok_or(Error::msg("Snapshot frame has no caps"))?;
+    let info = gst_video::VideoInfo::from_caps(caps)?;
+    let buffer = sample
+        .buffer_owned()
+        .ok_or(Error::msg("Snapshot frame has no data"))?;
+
+    let frame = gst_video::VideoFrame::from_buffer_readable(buffer, &info)
+        .map_err(|_| Error::msg("Failed to map the snapshot frame"))?;
+    let copy = gst::Buffer::with_size(info.size())?;
+    let mut copy = gst_video::VideoFrame::from_buffer_writable(copy, &info)
+        .map_err(|_| Error::msg("Failed to map the snapshot copy"))?;
+    frame.copy(&mut copy)?;
+
+    Ok(gst::Sample::builder()
+        .buffer(&copy.into_buffer())
+        .caps(&info.to_caps()?)
+        .build())
+}
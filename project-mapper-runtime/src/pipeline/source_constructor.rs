@@ -2,42 +2,121 @@ use anyhow::Error;
 use glib::clone::Downgrade;
 use gst::{
     Element, element_error, element_warning,
-    prelude::{ElementExt, ElementExtManual, GstBinExtManual, GstObjectExt, PadExt},
+    prelude::{
+        Cast, DeviceExt, ElementExt, ElementExtManual, GhostPadExt, GstBinExt, GstBinExtManual,
+        GstObjectExt, ObjectExt, PadExt, PadExtManual,
+    },
 };
-use project_mapper_core::config::source::{self, SourceType};
-use std::sync::{Arc, Mutex};
+use project_mapper_core::config::{
+    options::SourceTypeOptions,
+    sink::Resolution,
+    source::{self, AudioOutput, Fallback, SourceConfig, SourceType},
+};
+use std::collections::HashSet;
+use std::str::FromStr;
+use std::sync::{Arc, LazyLock, Mutex, RwLock};
+
+use crate::runtime::options;
 
 #[derive(Clone, Debug, glib::Boxed)]
 #[boxed_type(name = "ErrorValue")]
 struct ErrorValue(Arc<Mutex<Option<Error>>>);
 
 pub trait SourceTypeConstructor {
+    /// Checks the source's settings before any element is created
+    fn validate(&self) -> Result<(), glib::BoolError> {
+        Ok(())
+    }
+
     fn create_element(&self, id: String) -> Result<Element, glib::BoolError>;
     fn initialize_element(
         &self,
+        source_config: &SourceConfig,
         src_element: &gst::Element,
         sink_element: &gst::Element,
         pipeline: &gst::Pipeline,
     ) -> Result<(), glib::error::BoolError>;
+
+    /// Called once the pipeline has prerolled, before it starts playing
+    fn prepare_element(&self, _element: &gst::Element) -> Result<(), glib::BoolError> {
+        Ok(())
+    }
+
+    /// Applies new settings to an element created by `create_element` while it is running
+    fn update_element(&self, element: &gst::Element) -> Result<(), glib::BoolError> {
+        Err(glib::bool_error!(
+            "Source {} can't be updated while running",
+            element.name()
+        ))
+    }
 }
 
 impl SourceTypeConstructor for &source::Test {
     fn create_element(&self, id: String) -> Result<Element, glib::BoolError> {
         let name = format!("test-{}", id);
-        gst::ElementFactory::make("videotestsrc").name(name).build()
+
+        let mut src_builder =
+            gst::ElementFactory::make("videotestsrc").name(format!("{}-src", name));
+        if let Some(pattern) = &self.pattern {
+            // property_from_str panics on unknown values so check against the element's enum
+            let patterns = enum_values("videotestsrc", "pattern")?;
+            if !patterns.contains(pattern) {
+                return Err(glib::bool_error!(
+                    "Unknown test pattern {pattern} supported patterns: {patterns:?}"
+                ));
+            }
+            src_builder = src_builder.property_from_str("pattern", pattern);
+        }
+        let src = src_builder.build()?;
+
+        let capsfilter = gst::ElementFactory::make("capsfilter")
+            .name(format!("{}-caps", name))
+            .property("caps", video_caps(self.resolution.as_ref(), self.framerate))
+            .build()?;
+
+        let mut elements = vec![src, capsfilter];
+        let overlay_factory = match self.overlay {
+            source::TestOverlay::None => None,
+            source::TestOverlay::Timecode => Some("timeoverlay"),
+            source::TestOverlay::Clock => Some("clockoverlay"),
+        };
+        if let Some(factory) = overlay_factory {
+            let overlay = gst::ElementFactory::make(factory)
+                .name(format!("{}-overlay", name))
+                .property_from_str("halignment", "center")
+                .property_from_str("valignment", "bottom")
+                .build()?;
+            elements.push(overlay);
+        }
+
+        source_bin(&name, &elements)
     }
 
     fn initialize_element(
         &self,
+        _source_config: &SourceConfig,
         src_element: &gst::Element,
         sink_element: &gst::Element,
-        pipeline: &gst::Pipeline,
+        _pipeline: &gst::Pipeline,
     ) -> Result<(), glib::error::BoolError> {
         src_element.link(sink_element)
     }
 }
 
 impl SourceTypeConstructor for &source::URI {
+    fn validate(&self) -> Result<(), glib::BoolError> {
+        let playback = &self.playback;
+        if playback.rate == 0.0 {
+            return Err(glib::bool_error!("Playback rate can't be 0"));
+        }
+        if let (Some(in_point), Some(out_point)) = (playback.in_point_ms, playback.out_point_ms) {
+            if out_point <= in_point {
+                return Err(glib::bool_error!("Out point must be after the in point"));
+            }
+        }
+        Ok(())
+    }
+
     fn create_element(&self, id: String) -> Result<Element, glib::BoolError> {
         let name = format!("uri-{}", id);
         gst::ElementFactory::make("uridecodebin")
@@ -48,162 +127,1304 @@ impl SourceTypeConstructor for &source::URI {
 
     fn initialize_element(
         &self,
+        source_config: &SourceConfig,
         src_element: &gst::Element,
         sink_element: &gst::Element,
         pipeline: &gst::Pipeline,
     ) -> Result<(), glib::error::BoolError> {
-        // Need to move a new reference into the closure.
-        // !!ATTENTION!!:
-        // It might seem appealing to use pipeline.clone() here, because that greatly
-        // simplifies the code within the callback. What this actually does, however, is creating
-        // a memory leak. The clone of a pipeline is a new strong reference on the pipeline.
-        // Storing this strong reference of the pipeline within the callback (we are moving it in!),
-        // which is in turn stored in another strong reference on the pipeline is creating a
-        // reference cycle.
-        // DO NOT USE pipeline.clone() TO USE THE PIPELINE WITHIN A CALLBACK
-        let pipeline_weak = pipeline.downgrade();
-
-        // Clone sink element so it can be refenced in a callback
-        let sink_element = sink_element.clone();
-
-        // Connect to decodebin's pad-added signal, that is emitted whenever
-        // it found another stream from the input file and found a way to decode it to its raw format.
-        // decodebin automatically adds a src-pad for this raw stream, which
-        // we can use to build the follow-up pipeline.
-        src_element.connect_pad_added(move |dbin, src_pad| {
-            // Here we temporarily retrieve a strong reference on the pipeline from the weak one
-            // we moved into this callback.
-            let Some(pipeline) = pipeline_weak.upgrade() else {
-                return;
-            };
+        link_decoded_pads(
+            source_config,
+            src_element,
+            sink_element,
+            pipeline,
+            self.alpha,
+        )
+    }
+
+    /// Seeks to the configured in point, the seek's stop position ends the segment at the out point
+    fn prepare_element(&self, element: &gst::Element) -> Result<(), glib::BoolError> {
+        let playback = &self.playback;
+        if *playback == source::Playback::default() {
+            return Ok(());
+        }
+
+        let in_point = gst::ClockTime::from_mseconds(playback.in_point_ms.unwrap_or(0));
+        let out_point = playback.out_point_ms.map(gst::ClockTime::from_mseconds);
+
+        // any decoded pad will do, the seek travels upstream to the demuxer
+        let pad = element
+            .src_pads()
+            .into_iter()
+            .next()
+            .ok_or(glib::bool_error!(
+                "{} has no decoded pads to seek",
+                element.name()
+            ))?;
+
+        let offset = gst::ClockTime::from_mseconds(playback.start_offset_ms);
+        let (start, stop) = if playback.rate > 0.0 {
+            (in_point + offset, out_point)
+        } else if playback.start_offset_ms == 0 {
+            (in_point, out_point)
+        } else {
+            // reverse playback runs from stop to start so the offset is taken from the end
+            let end = out_point
+                .or_else(|| pad.query_duration::<gst::ClockTime>())
+                .ok_or(glib::bool_error!(
+                    "Duration of {} is unknown, set an out point",
+                    element.name()
+                ))?;
+            (in_point, Some(end.saturating_sub(offset)))
+        };
+
+        let stop_type = match stop {
+            Some(_) => gst::SeekType::Set,
+            None => gst::SeekType::None,
+        };
+        let seek = gst::event::Seek::new(
+            playback.rate,
+            gst::SeekFlags::FLUSH | gst::SeekFlags::ACCURATE,
+            gst::SeekType::Set,
+            Some(start),
+            stop_type,
+            stop,
+        );
+        if !pad.send_event(seek) {
+            return Err(glib::bool_error!("Seek on {} failed", element.name()));
+        }
+        Ok(())
+    }
+}
+
+/// Links the raw pads a decodebin style element adds at runtime, video goes to the sink element
+/// and audio to the source's audio output when one is configured
+fn link_decoded_pads(
+    source_config: &SourceConfig,
+    src_element: &gst::Element,
+    sink_element: &gst::Element,
+    pipeline: &gst::Pipeline,
+    alpha: bool,
+) -> Result<(), glib::error::BoolError> {
+    // Need to move a new reference into the closure.
+    // !!ATTENTION!!:
+    // It might seem appealing to use pipeline.clone() here, because that greatly
+    // simplifies the code within the callback. What this actually does, however, is creating
+    // a memory leak. The clone of a pipeline is a new strong reference on the pipeline.
+    // Storing this strong reference of the pipeline within the callback (we are moving it in!),
+    // which is in turn stored in another strong reference on the pipeline is creating a
+    // reference cycle.
+    // DO NOT USE pipeline.clone() TO USE THE PIPELINE WITHIN A CALLBACK
+    let pipeline_weak = pipeline.downgrade();
+
+    // decoded video goes straight to the sink element unless alpha needs converting first
+    let video_pad = if alpha {
+        // convert to RGBA here, otherwise negotiation may settle on a format without an alpha
+        // channel. Built once so a restarted source only relinks its new pad
+        let convert = gst::ElementFactory::make("videoconvert").build()?;
+        let capsfilter = gst::ElementFactory::make("capsfilter")
+            .property(
+                "caps",
+                gst_video::VideoCapsBuilder::new()
+                    .format(gst_video::VideoFormat::Rgba)
+                    .build(),
+            )
+            .build()?;
+        let elements = [&convert, &capsfilter, sink_element];
+        pipeline.add_many(&elements[..2])?;
+        gst::Element::link_many(elements)?;
+        for e in &elements[..2] {
+            e.sync_state_with_parent()?;
+        }
+        convert.static_pad("sink").expect("convert has no sinkpad")
+    } else {
+        sink_element
+            .static_pad("sink")
+            .expect("input has no sinkpad")
+    };
+    let audio_config = source_config.audio.clone();
+
+    // audio branches by the decoded pad feeding them, a restarted source removes its pads and
+    // adds new ones so the branches of the old pads have to go
+    let audio_branches: Arc<Mutex<Vec<(gst::Pad, Vec<gst::Element>)>>> =
+        Arc::new(Mutex::new(vec![]));
+    let removed_branches = audio_branches.clone();
+    let removed_pipeline_weak = pipeline_weak.clone();
+    src_element.connect_pad_removed(move |_, src_pad| {
+        let Some(pipeline) = removed_pipeline_weak.upgrade() else {
+            return;
+        };
+        let mut branches = removed_branches.lock().unwrap();
+        let Some(index) = branches.iter().position(|(pad, _)| pad == src_pad) else {
+            return;
+        };
+        let (_, elements) = branches.remove(index);
+        // stop the sink first so nothing upstream stays blocked on it
+        for element in elements.iter().rev() {
+            let _ = element.set_state(gst::State::Null);
+        }
+        let _ = pipeline.remove_many(&elements);
+    });
+
+    // Connect to decodebin's pad-added signal, that is emitted whenever
+    // it found another stream from the input file and found a way to decode it to its raw format.
+    // decodebin automatically adds a src-pad for this raw stream, which
+    // we can use to build the follow-up pipeline.
+    src_element.connect_pad_added(move |dbin, src_pad| {
+        // Here we temporarily retrieve a strong reference on the pipeline from the weak one
+        // we moved into this callback.
+        let Some(pipeline) = pipeline_weak.upgrade() else {
+            return;
+        };
+
+        // Try to detect whether the raw stream decodebin provided us with
+        // just now is either audio or video (or none of both, e.g. subtitles).
+        let (is_audio, is_video) = {
+            let media_type = src_pad.current_caps().and_then(|caps| {
+                caps.structure(0).map(|s| {
+                    let name = s.name();
+                    (name.starts_with("audio/"), name.starts_with("video/"))
+                })
+            });
+
+            match media_type {
+                None => {
+                    element_warning!(
+                        dbin,
+                        gst::CoreError::Negotiation,
+                        ("Failed to get media type from pad {}", src_pad.name())
+                    );
+
+                    return;
+                }
+                Some(media_type) => media_type,
+            }
+        };
 
-            // Try to detect whether the raw stream decodebin provided us with
-            // just now is either audio or video (or none of both, e.g. subtitles).
-            let (is_audio, is_video) = {
-                let media_type = src_pad.current_caps().and_then(|caps| {
-                    caps.structure(0).map(|s| {
-                        let name = s.name();
-                        (name.starts_with("audio/"), name.starts_with("video/"))
-                    })
+        // We create a closure here, calling it directly below it, because this greatly
+        // improves readability for error-handling. Like this, we can simply use the
+        // ?-operator within the closure, and handle the actual error down below where
+        // we call the insert_sink(..) closure.
+        let insert_sink = |is_audio, is_video| -> Result<(), Error> {
+            if is_video {
+                // decodebin found a raw videostream, link it to the source's input.
+                src_pad.link(&video_pad)?;
+            } else if let (true, Some(audio_config)) = (is_audio, &audio_config) {
+                // decodebin found a raw audiostream, play it through the configured output.
+                // Sharing the pipeline clock keeps it in sync with the video.
+                let queue = gst::ElementFactory::make("queue").build()?;
+                let convert = gst::ElementFactory::make("audioconvert").build()?;
+                let resample = gst::ElementFactory::make("audioresample").build()?;
+                let volume = gst::ElementFactory::make("volume")
+                    .property("volume", audio_config.volume)
+                    .property("mute", audio_config.mute)
+                    .build()?;
+                let audio_sink = create_audio_sink(&audio_config.output)?;
+
+                let elements = &[&queue, &convert, &resample, &volume, &audio_sink];
+                pipeline.add_many(elements)?;
+                gst::Element::link_many(elements)?;
+
+                // offset the running time of the audio to correct A/V sync
+                let offset_ns = audio_config.offset_ms * gst::ClockTime::MSECOND.nseconds() as i64;
+                queue
+                    .static_pad("src")
+                    .expect("queue has no srcpad")
+                    .set_offset(offset_ns);
+
+                for e in elements {
+                    e.sync_state_with_parent()?
+                }
+
+                let sink_pad = queue.static_pad("sink").expect("queue has no sinkpad");
+                src_pad.link(&sink_pad)?;
+
+                audio_branches.lock().unwrap().push((
+                    src_pad.clone(),
+                    elements.iter().map(|e| (*e).clone()).collect(),
+                ));
+            }
+
+            Ok(())
+        };
+
+        // When adding and linking new elements in a callback fails, error information is often sparse.
+        // GStreamer's built-in debugging can be hard to link back to the exact position within the code
+        // that failed. Since callbacks are called from random threads within the pipeline, it can get hard
+        // to get good error information. The macros used in the following can solve that. With the use
+        // of those, one can send arbitrary rust types (using the pipeline's bus) into the mainloop.
+        // What we send here is unpacked down below, in the iteration-code over sent bus-messages.
+        // Because we are using the failure crate for error details here, we even get a backtrace for
+        // where the error was constructed. (If RUST_BACKTRACE=1 is set)
+        if let Err(err) = insert_sink(is_audio, is_video) {
+            // The following sends a message of type Error on the bus, containing our detailed
+            // error information.
+            element_error!(
+                dbin,
+                gst::LibraryError::Failed,
+                ("Failed to insert sink"),
+                details: gst::Structure::builder("error-details")
+                            .field("error",
+                                   ErrorValue(Arc::new(Mutex::new(Some(err)))))
+                            .build()
+            );
+        }
+    });
+    Ok(())
+}
+
+impl SourceTypeConstructor for &source::Capture {
+    fn create_element(&self, id: String) -> Result<Element, glib::BoolError> {
+        let name = format!("capture-{}", id);
+
+        // prefer the device monitor so any registered provider works, fall back to v4l2src
+        let src_name = format!("{}-src", name);
+        let src = match find_capture_device(&self.device) {
+            Some(device) => device.create_element(Some(src_name.as_str()))?,
+            None => gst::ElementFactory::make("v4l2src")
+                .name(src_name)
+                .property("device", self.device.clone())
+                .build()?,
+        };
+
+        let caps = match &self.caps {
+            Some(caps) => gst::Caps::from_str(caps)
+                .map_err(|_| glib::bool_error!("Invalid capture caps {caps}"))?,
+            None => video_caps(self.resolution.as_ref(), self.framerate),
+        };
+        let is_jpeg = caps
+            .structure(0)
+            .is_some_and(|s| s.name().as_str() == "image/jpeg");
+
+        let capsfilter = gst::ElementFactory::make("capsfilter")
+            .name(format!("{}-caps", name))
+            .property("caps", caps)
+            .build()?;
+
+        let mut elements = vec![src, capsfilter];
+        if is_jpeg {
+            elements.push(gst::ElementFactory::make("jpegdec").build()?);
+        }
+        elements.push(gst::ElementFactory::make("videoconvert").build()?);
+
+        source_bin(&name, &elements)
+    }
+
+    fn initialize_element(
+        &self,
+        _source_config: &SourceConfig,
+        src_element: &gst::Element,
+        sink_element: &gst::Element,
+        _pipeline: &gst::Pipeline,
+    ) -> Result<(), glib::error::BoolError> {
+        src_element.link(sink_element)
+    }
+}
+
+impl SourceTypeConstructor for &source::Screen {
+    fn create_element(&self, id: String) -> Result<Element, glib::BoolError> {
+        let name = format!("screen-{}", id);
+
+        let mut src_builder = gst::ElementFactory::make("ximagesrc")
+            .name(format!("{}-src", name))
+            .property("show-pointer", self.show_cursor)
+            // damage events only help for mostly static desktops and cause tearing otherwise
+            .property("use-damage", false);
+        if let Some(display) = &self.display {
+            src_builder = src_builder.property("display-name", display.clone());
+        }
+        src_builder = match &self.area {
+            source::ScreenArea::FullScreen => src_builder,
+            source::ScreenArea::Region {
+                x,
+                y,
+                width,
+                height,
+            } => {
+                if *width == 0 || *height == 0 {
+                    return Err(glib::bool_error!("Screen region must have a non zero size"));
+                }
+                // end coordinates are inclusive
+                src_builder
+                    .property("startx", *x)
+                    .property("starty", *y)
+                    .property("endx", x + width - 1)
+                    .property("endy", y + height - 1)
+            }
+            source::ScreenArea::Window { id, name } => match (id, name) {
+                (Some(id), _) => src_builder.property("xid", *id),
+                (None, Some(name)) => src_builder.property("xname", name.clone()),
+                (None, None) => {
+                    return Err(glib::bool_error!(
+                        "Window screen capture needs either an id or a name"
+                    ));
+                }
+            },
+        };
+        let src = src_builder.build()?;
+
+        let capsfilter = gst::ElementFactory::make("capsfilter")
+            .name(format!("{}-caps", name))
+            .property("caps", video_caps(None, self.framerate))
+            .build()?;
+        let convert = gst::ElementFactory::make("videoconvert").build()?;
+
+        source_bin(&name, &[src, capsfilter, convert])
+    }
+
+    fn initialize_element(
+        &self,
+        _source_config: &SourceConfig,
+        src_element: &gst::Element,
+        sink_element: &gst::Element,
+        _pipeline: &gst::Pipeline,
+    ) -> Result<(), glib::error::BoolError> {
+        src_element.link(sink_element)
+    }
+}
+
+impl SourceTypeConstructor for &source::Pipeline {
+    fn create_element(&self, id: String) -> Result<Element, glib::BoolError> {
+        let name = format!("pipeline-{}", id);
+        let description = &self.description;
+
+        // ghost the unlinked src pad so the bin can be linked like any other source
+        let bin = gst::parse::bin_from_description(description, true).map_err(|err| {
+            glib::bool_error!("Failed to parse pipeline source `{description}`: {err}")
+        })?;
+        bin.set_property("name", name.as_str());
+
+        let src_pad = bin.static_pad("src").ok_or(glib::bool_error!(
+            "Pipeline source `{description}` has no unlinked src pad"
+        ))?;
+
+        // elements with sometimes pads report ANY here so only reject caps we know aren't video
+        let caps = src_pad.query_caps(None);
+        if !caps.is_any() && !caps.iter().any(|s| s.name().starts_with("video/")) {
+            return Err(glib::bool_error!(
+                "Pipeline source `{description}` does not produce video, its src pad has caps {caps}"
+            ));
+        }
+
+        Ok(bin.upcast())
+    }
+
+    fn initialize_element(
+        &self,
+        _source_config: &SourceConfig,
+        src_element: &gst::Element,
+        sink_element: &gst::Element,
+        _pipeline: &gst::Pipeline,
+    ) -> Result<(), glib::error::BoolError> {
+        src_element.link(sink_element)
+    }
+}
+
+impl SourceTypeConstructor for &source::Color {
+    fn create_element(&self, id: String) -> Result<Element, glib::BoolError> {
+        let name = format!("color-{}", id);
+
+        let src = gst::ElementFactory::make("videotestsrc")
+            .name(format!("{}-src", name))
+            .property_from_str("pattern", "solid-color")
+            .property("is-live", true)
+            .build()?;
+        let capsfilter = gst::ElementFactory::make("capsfilter")
+            .name(format!("{}-caps", name))
+            .build()?;
+
+        let element = source_bin(&name, &[src, capsfilter])?;
+        self.update_element(&element)?;
+        Ok(element)
+    }
+
+    fn initialize_element(
+        &self,
+        _source_config: &SourceConfig,
+        src_element: &gst::Element,
+        sink_element: &gst::Element,
+        _pipeline: &gst::Pipeline,
+    ) -> Result<(), glib::error::BoolError> {
+        src_element.link(sink_element)
+    }
+
+    fn update_element(&self, element: &gst::Element) -> Result<(), glib::BoolError> {
+        let src = child_element(element, "src")?;
+        src.set_property("foreground-color", self.color.to_argb());
+
+        let capsfilter = child_element(element, "caps")?;
+        capsfilter.set_property("caps", video_caps(self.resolution.as_ref(), self.framerate));
+        Ok(())
+    }
+}
+
+impl SourceTypeConstructor for &source::Text {
+    fn create_element(&self, id: String) -> Result<Element, glib::BoolError> {
+        let name = format!("text-{}", id);
+
+        let src = gst::ElementFactory::make("videotestsrc")
+            .name(format!("{}-src", name))
+            .property_from_str("pattern", "solid-color")
+            .property("is-live", true)
+            .build()?;
+        let capsfilter = gst::ElementFactory::make("capsfilter")
+            .name(format!("{}-caps", name))
+            .build()?;
+        let overlay = gst::ElementFactory::make("textoverlay")
+            .name(format!("{}-overlay", name))
+            .property("shaded-background", false)
+            .property_from_str("wrap-mode", "word")
+            .build()?;
+
+        let element = source_bin(&name, &[src, capsfilter, overlay])?;
+        self.update_element(&element)?;
+        Ok(element)
+    }
+
+    fn initialize_element(
+        &self,
+        _source_config: &SourceConfig,
+        src_element: &gst::Element,
+        sink_element: &gst::Element,
+        _pipeline: &gst::Pipeline,
+    ) -> Result<(), glib::error::BoolError> {
+        src_element.link(sink_element)
+    }
+
+    fn update_element(&self, element: &gst::Element) -> Result<(), glib::BoolError> {
+        let src = child_element(element, "src")?;
+        src.set_property("foreground-color", self.background.to_argb());
+
+        let capsfilter = child_element(element, "caps")?;
+        capsfilter.set_property("caps", video_caps(self.resolution.as_ref(), self.framerate));
+
+        let font = self.font.clone().unwrap_or(String::from("Sans"));
+        let font_desc = match self.size {
+            Some(size) => format!("{font} {size}"),
+            None => font,
+        };
+        let halignment = match self.horizontal_alignment {
+            source::HorizontalAlignment::Left => "left",
+            source::HorizontalAlignment::Center => "center",
+            source::HorizontalAlignment::Right => "right",
+        };
+        let valignment = match self.vertical_alignment {
+            source::VerticalAlignment::Top => "top",
+            source::VerticalAlignment::Center => "center",
+            source::VerticalAlignment::Bottom => "bottom",
+        };
+
+        let overlay = child_element(element, "overlay")?;
+        overlay.set_property("text", self.text.as_str());
+        overlay.set_property("font-desc", font_desc.as_str());
+        overlay.set_property("color", self.color.to_argb());
+        overlay.set_property_from_str("halignment", halignment);
+        overlay.set_property_from_str("valignment", valignment);
+        Ok(())
+    }
+}
+
+impl SourceTypeConstructor for &source::NetworkStream {
+    fn validate(&self) -> Result<(), glib::BoolError> {
+        let uri = &self.uri;
+        let schemes: &[&str] = match self.protocol {
+            source::StreamProtocol::Rtsp => &["rtsp", "rtsps", "rtspt", "rtspu"],
+            source::StreamProtocol::Srt => &["srt"],
+            source::StreamProtocol::Rtp => &["udp"],
+        };
+        let scheme = uri.split("://").next().unwrap_or_default();
+        if !uri.contains("://") || !schemes.contains(&scheme) {
+            return Err(glib::bool_error!(
+                "Stream uri {uri} does not match protocol {:?}, expected one of {schemes:?}",
+                self.protocol
+            ));
+        }
+        if self.protocol == source::StreamProtocol::Rtp && self.caps.is_none() {
+            return Err(glib::bool_error!("RTP streams need caps"));
+        }
+        Ok(())
+    }
+
+    fn create_element(&self, id: String) -> Result<Element, glib::BoolError> {
+        let name = format!("stream-{}", id);
+        let uri = &self.uri;
+
+        match self.protocol {
+            source::StreamProtocol::Rtp => self.create_rtp_element(name),
+            _ => {
+                let element = gst::ElementFactory::make("uridecodebin")
+                    .name(name)
+                    .property("uri", uri.clone())
+                    .build()?;
+
+                // tune the network element once uridecodebin has picked it
+                let config = (*self).clone();
+                element.connect("source-setup", false, move |args| {
+                    if let Some(Ok(source)) = args.get(1).map(|v| v.get::<gst::Element>()) {
+                        config.configure_source(&source);
+                    }
+                    None
                 });
+                Ok(element)
+            }
+        }
+    }
+
+    fn initialize_element(
+        &self,
+        source_config: &SourceConfig,
+        src_element: &gst::Element,
+        sink_element: &gst::Element,
+        pipeline: &gst::Pipeline,
+    ) -> Result<(), glib::error::BoolError> {
+        link_decoded_pads(source_config, src_element, sink_element, pipeline, false)
+    }
+}
 
-                match media_type {
-                    None => {
-                        element_warning!(
-                            dbin,
-                            gst::CoreError::Negotiation,
-                            ("Failed to get media type from pad {}", src_pad.name())
-                        );
+trait NetworkStreamExt {
+    fn configure_source(&self, source: &gst::Element);
+    fn create_rtp_element(&self, name: String) -> Result<Element, glib::BoolError>;
+}
 
-                        return;
+impl NetworkStreamExt for source::NetworkStream {
+    fn configure_source(&self, source: &gst::Element) {
+        match self.protocol {
+            source::StreamProtocol::Rtsp => {
+                if let Some(latency) = self.latency_ms {
+                    source.set_property("latency", latency);
+                }
+                match self.transport {
+                    source::StreamTransport::Auto => {}
+                    source::StreamTransport::Tcp => {
+                        source.set_property_from_str("protocols", "tcp")
+                    }
+                    source::StreamTransport::Udp => {
+                        source.set_property_from_str("protocols", "udp+udp-mcast")
                     }
-                    Some(media_type) => media_type,
                 }
-            };
+                if let Some(timeout) = self.timeout_ms {
+                    // rtspsrc timeouts are in microseconds
+                    source.set_property("timeout", timeout * 1000);
+                    source.set_property("tcp-timeout", timeout * 1000);
+                }
+            }
+            source::StreamProtocol::Srt => {
+                if let Some(latency) = self.latency_ms {
+                    source.set_property("latency", latency as i32);
+                }
+                if let Some(timeout) = self.timeout_ms {
+                    source.set_property("poll-timeout", timeout as i32);
+                }
+            }
+            source::StreamProtocol::Rtp => {}
+        }
+    }
 
-            // We create a closure here, calling it directly below it, because this greatly
-            // improves readability for error-handling. Like this, we can simply use the
-            // ?-operator within the closure, and handle the actual error down below where
-            // we call the insert_sink(..) closure.
-            let insert_sink =
-                |is_audio, is_video, sink_element: &gst::Element| -> Result<(), Error> {
-                    if is_video {
-                        // decodebin found a raw videostream, so we build the follow-up pipeline to
-                        // display it using the autovideosink.
-                        let queue = gst::ElementFactory::make("queue").build()?;
-                        let convert = gst::ElementFactory::make("videoconvert").build()?;
-                        let scale = gst::ElementFactory::make("videoscale").build()?;
-
-                        let elements = &[&queue, &convert, &scale];
-                        pipeline.add_many(elements)?;
-                        gst::Element::link_many(elements)?;
-
-                        for e in elements {
-                            e.sync_state_with_parent()?
-                        }
-
-                        // Get the queue element's sink pad and link the decodebin's newly created
-                        // src pad for the video stream to it.
-                        let sink_pad = sink_element
-                            .static_pad("sink")
-                            .expect("queue has no sinkpad");
-                        src_pad.link(&sink_pad)?;
-                    }
+    /// uridecodebin can't add a jitter buffer for raw RTP so build udpsrc ! rtpjitterbuffer ! decodebin
+    fn create_rtp_element(&self, name: String) -> Result<Element, glib::BoolError> {
+        let caps = self
+            .caps
+            .as_ref()
+            .ok_or(glib::bool_error!("RTP streams need caps"))?;
+        let caps =
+            gst::Caps::from_str(caps).map_err(|_| glib::bool_error!("Invalid RTP caps {caps}"))?;
 
-                    Ok(())
-                };
-
-            // When adding and linking new elements in a callback fails, error information is often sparse.
-            // GStreamer's built-in debugging can be hard to link back to the exact position within the code
-            // that failed. Since callbacks are called from random threads within the pipeline, it can get hard
-            // to get good error information. The macros used in the following can solve that. With the use
-            // of those, one can send arbitrary rust types (using the pipeline's bus) into the mainloop.
-            // What we send here is unpacked down below, in the iteration-code over sent bus-messages.
-            // Because we are using the failure crate for error details here, we even get a backtrace for
-            // where the error was constructed. (If RUST_BACKTRACE=1 is set)
-            if let Err(err) = insert_sink(is_audio, is_video, &sink_element) {
-                // The following sends a message of type Error on the bus, containing our detailed
-                // error information.
-                element_error!(
-                    dbin,
-                    gst::LibraryError::Failed,
-                    ("Failed to insert sink"),
-                    details: gst::Structure::builder("error-details")
-                                .field("error",
-                                       ErrorValue(Arc::new(Mutex::new(Some(err)))))
-                                .build()
+        let mut udpsrc = gst::ElementFactory::make("udpsrc")
+            .name(format!("{}-src", name))
+            .property("uri", self.uri.clone())
+            .property("caps", caps);
+        if let Some(timeout) = self.timeout_ms {
+            udpsrc = udpsrc.property("timeout", timeout * gst::ClockTime::MSECOND.nseconds());
+        }
+        let udpsrc = udpsrc.build()?;
+
+        let mut jitterbuffer =
+            gst::ElementFactory::make("rtpjitterbuffer").name(format!("{}-jitterbuffer", name));
+        if let Some(latency) = self.latency_ms {
+            jitterbuffer = jitterbuffer.property("latency", latency);
+        }
+        let jitterbuffer = jitterbuffer.build()?;
+        let decodebin = gst::ElementFactory::make("decodebin")
+            .name(format!("{}-decode", name))
+            .build()?;
+
+        let bin = gst::Bin::builder().name(name).build();
+        let elements = [udpsrc, jitterbuffer, decodebin];
+        bin.add_many(&elements)?;
+        gst::Element::link_many(&elements)?;
+
+        // re-expose decoded pads on the bin so it behaves like uridecodebin
+        let bin_weak = bin.downgrade();
+        elements[2].connect_pad_added(move |_, src_pad| {
+            let Some(bin) = bin_weak.upgrade() else {
+                return;
+            };
+            let ghost_pad = gst::GhostPad::with_target(src_pad).and_then(|ghost_pad| {
+                ghost_pad.set_active(true)?;
+                bin.add_pad(&ghost_pad)
+            });
+            if let Err(err) = ghost_pad {
+                element_warning!(
+                    bin,
+                    gst::CoreError::Pad,
+                    ("Failed to expose decoded pad {}: {}", src_pad.name(), err)
                 );
             }
         });
+
+        // drop ghost pads whose decoded pad went away so a restarted source can expose new ones
+        let bin_weak = bin.downgrade();
+        elements[2].connect_pad_removed(move |_, src_pad| {
+            let Some(bin) = bin_weak.upgrade() else {
+                return;
+            };
+            for pad in bin.src_pads() {
+                let ghost_pad = pad
+                    .downcast::<gst::GhostPad>()
+                    .expect("bin pads are ghost pads");
+                if ghost_pad.target().is_none_or(|target| &target == src_pad) {
+                    let _ = ghost_pad.set_active(false);
+                    let _ = bin.remove_pad(&ghost_pad);
+                }
+            }
+        });
+
+        Ok(bin.upcast())
+    }
+}
+
+impl SourceTypeConstructor for &source::ImageSequence {
+    fn validate(&self) -> Result<(), glib::BoolError> {
+        if !std::path::Path::new(&self.directory).is_dir() {
+            return Err(glib::bool_error!(
+                "Image sequence directory {} does not exist",
+                self.directory
+            ));
+        }
+        if self
+            .end_frame
+            .is_some_and(|end_frame| end_frame < self.start_frame)
+        {
+            return Err(glib::bool_error!(
+                "End frame must not be before the start frame"
+            ));
+        }
+        image_decoder(&self.pattern).map(|_| ())
+    }
+
+    fn create_element(&self, id: String) -> Result<Element, glib::BoolError> {
+        let name = format!("sequence-{}", id);
+
+        let (media_type, decoder) = image_decoder(&self.pattern)?;
+        let framerate = self.framerate.unwrap_or(DEFAULT_SEQUENCE_FRAMERATE) as i32;
+        let caps = gst::Caps::builder(media_type)
+            .field("framerate", gst::Fraction::new(framerate, 1))
+            .build();
+        let location = std::path::Path::new(&self.directory).join(&self.pattern);
+
+        let mut src = gst::ElementFactory::make("multifilesrc")
+            .name(format!("{}-src", name))
+            .property("location", location.to_string_lossy().to_string())
+            .property("index", self.start_frame as i32)
+            .property("start-index", self.start_frame as i32)
+            .property("loop", self.looping)
+            .property("caps", caps);
+        if let Some(end_frame) = self.end_frame {
+            src = src.property("stop-index", end_frame as i32);
+        }
+        let src = src.build()?;
+
+        let decoder = gst::ElementFactory::make(decoder)
+            .name(format!("{}-decode", name))
+            .build()?;
+        let convert = gst::ElementFactory::make("videoconvert")
+            .name(format!("{}-convert", name))
+            .build()?;
+
+        // only bound the queue by frame count so large frames still preload
+        let preload = gst::ElementFactory::make("queue")
+            .name(format!("{}-preload", name))
+            .property(
+                "max-size-buffers",
+                self.preload_frames.unwrap_or(DEFAULT_PRELOAD_FRAMES),
+            )
+            .property("max-size-bytes", 0u32)
+            .property("max-size-time", 0u64)
+            .build()?;
+
+        source_bin(&name, &[src, decoder, convert, preload])
+    }
+
+    fn initialize_element(
+        &self,
+        _source_config: &SourceConfig,
+        src_element: &gst::Element,
+        sink_element: &gst::Element,
+        _pipeline: &gst::Pipeline,
+    ) -> Result<(), glib::error::BoolError> {
+        src_element.link(sink_element)
+    }
+}
+
+const DEFAULT_SEQUENCE_FRAMERATE: u32 = 25;
+const DEFAULT_PRELOAD_FRAMES: u32 = 10;
+
+/// Image formats an image sequence can be made of as (extension, media type, decoder)
+const IMAGE_FORMATS: [(&str, &str, &str); 5] = [
+    ("png", "image/png", "pngdec"),
+    ("jpg", "image/jpeg", "jpegdec"),
+    ("jpeg", "image/jpeg", "jpegdec"),
+    ("tiff", "image/tiff", "avdec_tiff"),
+    ("exr", "image/x-exr", "openexrdec"),
+];
+
+/// Picks the media type and decoder for a sequence from the pattern's file extension
+fn image_decoder(pattern: &str) -> Result<(&'static str, &'static str), glib::BoolError> {
+    let extension = std::path::Path::new(pattern)
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let extension = if extension == "tif" {
+        "tiff".to_owned()
+    } else {
+        extension
+    };
+    IMAGE_FORMATS
+        .iter()
+        .find(|(format, _, _)| *format == extension)
+        .map(|(_, media_type, decoder)| (*media_type, *decoder))
+        .ok_or(glib::bool_error!(
+            "Unsupported image sequence format {pattern}"
+        ))
+}
+
+/// Image sequence extensions whose decoder is installed
+pub fn image_sequence_formats() -> Vec<String> {
+    IMAGE_FORMATS
+        .iter()
+        .filter(|(_, _, decoder)| gst::ElementFactory::find(decoder).is_some())
+        .map(|(format, _, _)| format.to_string())
+        .collect()
+}
+
+impl SourceTypeConstructor for &source::SharedMemory {
+    fn validate(&self) -> Result<(), glib::BoolError> {
+        if self.socket_path.is_empty() {
+            return Err(glib::bool_error!(
+                "Shared memory source needs a socket path"
+            ));
+        }
+        let caps = gst::Caps::from_str(&self.caps)
+            .map_err(|_| glib::bool_error!("Invalid shared memory caps {}", self.caps))?;
+        // shmsrc only moves bytes so the caps must fully describe raw frames
+        if !caps.is_fixed() || !caps.iter().all(|s| s.name() == "video/x-raw") {
+            return Err(glib::bool_error!(
+                "Shared memory caps {} must be fixed raw video",
+                self.caps
+            ));
+        }
         Ok(())
     }
+
+    fn create_element(&self, id: String) -> Result<Element, glib::BoolError> {
+        let name = format!("shm-{}", id);
+
+        // the producer runs in real time so timestamp frames on arrival
+        let src = gst::ElementFactory::make("shmsrc")
+            .name(format!("{}-src", name))
+            .property("socket-path", self.socket_path.clone())
+            .property("is-live", true)
+            .property("do-timestamp", true)
+            .build()?;
+        let capsfilter = gst::ElementFactory::make("capsfilter")
+            .name(format!("{}-caps", name))
+            .property(
+                "caps",
+                gst::Caps::from_str(&self.caps)
+                    .map_err(|_| glib::bool_error!("Invalid shared memory caps {}", self.caps))?,
+            )
+            .build()?;
+
+        source_bin(&name, &[src, capsfilter])
+    }
+
+    fn initialize_element(
+        &self,
+        _source_config: &SourceConfig,
+        src_element: &gst::Element,
+        sink_element: &gst::Element,
+        _pipeline: &gst::Pipeline,
+    ) -> Result<(), glib::error::BoolError> {
+        src_element.link(sink_element)
+    }
 }
 
-pub fn create_element(source: &SourceType, id: String) -> Result<Element, glib::BoolError> {
-    if let Ok(value) = get_uri_type(source) {
-        return value.create_element(id);
+/// Creates what a source's regions show while it is down, `None` when the fallback is another source
+pub fn create_fallback_element(
+    fallback: &Fallback,
+    id: String,
+) -> Result<Option<Element>, glib::BoolError> {
+    let name = format!("fallback-{}", id);
+    match fallback {
+        Fallback::Black => {
+            let src = gst::ElementFactory::make("videotestsrc")
+                .name(name)
+                .build()?;
+            src.set_property_from_str("pattern", "black");
+            Ok(Some(src))
+        }
+        Fallback::Image { path } => {
+            let (_, decoder) = image_decoder(path)?;
+            let src = gst::ElementFactory::make("filesrc")
+                .name(format!("{}-src", name))
+                .property("location", path.clone())
+                .build()?;
+            let decoder = gst::ElementFactory::make(decoder)
+                .name(format!("{}-decode", name))
+                .build()?;
+            // repeats the decoded image as a video stream
+            let freeze = gst::ElementFactory::make("imagefreeze")
+                .name(format!("{}-freeze", name))
+                .build()?;
+            let convert = gst::ElementFactory::make("videoconvert")
+                .name(format!("{}-convert", name))
+                .build()?;
+            Ok(Some(source_bin(&name, &[src, decoder, freeze, convert])?))
+        }
+        Fallback::Source { .. } => Ok(None),
     }
-    if let Ok(value) = get_test_type(source) {
-        return value.create_element(id);
+}
+
+/// A kind of source the runtime can build. Built in kinds are always registered, embedders add
+/// their own with `register_source_kind` and configure them as `SourceType::Custom`
+pub trait SourceKind: Send + Sync {
+    /// `SourceType` variant name, or the `kind` of custom sources
+    fn name(&self) -> &str;
+
+    /// Returns the constructor of a source when it is of this kind
+    fn constructor<'a>(
+        &self,
+        source: &'a SourceType,
+    ) -> Option<Box<dyn SourceTypeConstructor + 'a>>;
+
+    /// What `get-available-config` lists for this kind, `None` when unavailable on this system
+    fn options(&self) -> anyhow::Result<Option<SourceTypeOptions>>;
+}
+
+struct BuiltinSource {
+    name: &'static str,
+    constructor: for<'a> fn(&'a SourceType) -> Option<Box<dyn SourceTypeConstructor + 'a>>,
+    options: fn() -> anyhow::Result<Option<SourceTypeOptions>>,
+}
+
+impl SourceKind for BuiltinSource {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn constructor<'a>(
+        &self,
+        source: &'a SourceType,
+    ) -> Option<Box<dyn SourceTypeConstructor + 'a>> {
+        (self.constructor)(source)
+    }
+
+    fn options(&self) -> anyhow::Result<Option<SourceTypeOptions>> {
+        (self.options)()
+    }
+}
+
+fn builtin_sources() -> Vec<Box<dyn SourceKind>> {
+    let sources = [
+        BuiltinSource {
+            name: "URI",
+            constructor: get_uri_type,
+            options: || Ok(Some(options::generate_uri_option()?)),
+        },
+        BuiltinSource {
+            name: "Test",
+            constructor: get_test_type,
+            options: || Ok(Some(options::generate_test_option()?)),
+        },
+        BuiltinSource {
+            name: "Capture",
+            constructor: get_capture_type,
+            options: || Ok(Some(options::generate_capture_option()?)),
+        },
+        BuiltinSource {
+            name: "Screen",
+            constructor: get_screen_type,
+            options: options::generate_screen_option,
+        },
+        BuiltinSource {
+            name: "Pipeline",
+            constructor: get_pipeline_type,
+            options: || Ok(Some(SourceTypeOptions::Pipeline {})),
+        },
+        BuiltinSource {
+            name: "Color",
+            constructor: get_color_type,
+            options: || Ok(Some(SourceTypeOptions::Color {})),
+        },
+        BuiltinSource {
+            name: "Text",
+            constructor: get_text_type,
+            options: || Ok(Some(SourceTypeOptions::Text {})),
+        },
+        BuiltinSource {
+            name: "NetworkStream",
+            constructor: get_network_stream_type,
+            options: || Ok(Some(options::generate_network_stream_option()?)),
+        },
+        BuiltinSource {
+            name: "SharedMemory",
+            constructor: get_shared_memory_type,
+            options: || {
+                Ok(gst::ElementFactory::find("shmsrc").map(|_| SourceTypeOptions::SharedMemory {}))
+            },
+        },
+        BuiltinSource {
+            name: "ImageSequence",
+            constructor: get_image_sequence_type,
+            options: || {
+                Ok(Some(SourceTypeOptions::ImageSequence {
+                    formats: image_sequence_formats(),
+                }))
+            },
+        },
+    ];
+    sources
+        .into_iter()
+        .map(|source| Box::new(source) as Box<dyn SourceKind>)
+        .collect()
+}
+
+static SOURCE_KINDS: LazyLock<RwLock<Vec<Box<dyn SourceKind>>>> =
+    LazyLock::new(|| RwLock::new(builtin_sources()));
+
+/// Adds a source kind, it takes precedence over kinds registered before it with the same name
+pub fn register_source_kind(kind: impl SourceKind + 'static) {
+    SOURCE_KINDS.write().unwrap().insert(0, Box::new(kind));
+}
+
+/// Options of every registered source kind for `get-available-config`, a kind overridden by a
+/// later registration is only listed once and a kind that can't list its options is left out
+pub fn source_options() -> Vec<SourceTypeOptions> {
+    let mut options = vec![];
+    let mut names = HashSet::new();
+    for kind in SOURCE_KINDS.read().unwrap().iter() {
+        if !names.insert(kind.name().to_owned()) {
+            continue;
+        }
+        match kind.options() {
+            Ok(kind_options) => options.extend(kind_options),
+            Err(err) => eprintln!("Failed to list {} sources: {err}", kind.name()),
+        }
     }
-    Err(glib::BoolError::new(
-        "can't create element",
-        "pipeline",
-        "func",
-        1,
-    ))
+    options
+}
+
+fn get_constructor(
+    source: &SourceType,
+) -> Result<Box<dyn SourceTypeConstructor + '_>, glib::BoolError> {
+    SOURCE_KINDS
+        .read()
+        .unwrap()
+        .iter()
+        .find_map(|kind| kind.constructor(source))
+        .ok_or_else(|| match source {
+            SourceType::Custom(custom) => {
+                glib::bool_error!("No source kind {} is registered", custom.kind)
+            }
+            _ => glib::bool_error!("No source kind is registered for this source"),
+        })
+}
+
+pub fn create_element(source: &SourceType, id: String) -> Result<Element, glib::BoolError> {
+    let constructor = get_constructor(source)?;
+    constructor.validate()?;
+    constructor.create_element(id)
 }
 
 pub fn initialize_element(
-    config: &SourceType,
+    source_config: &SourceConfig,
     element: &gst::Element,
     sink: &gst::Element,
     pipeline: &gst::Pipeline,
 ) -> Result<(), glib::error::BoolError> {
-    if let Ok(value) = get_uri_type(config) {
-        return value.initialize_element(element, sink, pipeline);
-    }
-    if let Ok(value) = get_test_type(config) {
-        return value.initialize_element(element, sink, pipeline);
-    }
-    Err(glib::BoolError::new(
-        "can't init element",
-        "pipeline",
-        "func",
-        1,
-    ))
+    get_constructor(&source_config.source)?.initialize_element(
+        source_config,
+        element,
+        sink,
+        pipeline,
+    )
 }
 
-fn get_uri_type(config: &SourceType) -> anyhow::Result<impl SourceTypeConstructor> {
+pub fn prepare_element(source: &SourceType, element: &gst::Element) -> Result<(), glib::BoolError> {
+    get_constructor(source)?.prepare_element(element)
+}
+
+pub fn update_element(source: &SourceType, element: &gst::Element) -> Result<(), glib::BoolError> {
+    let constructor = get_constructor(source)?;
+    constructor.validate()?;
+    constructor.update_element(element)
+}
+
+fn get_uri_type(config: &SourceType) -> Option<Box<dyn SourceTypeConstructor + '_>> {
     if let SourceType::URI(uri) = config {
-        return Ok(uri);
+        return Some(Box::new(uri));
     }
-    Err(anyhow::Error::msg("Could not find constructor Type"))
+    None
 }
 
-fn get_test_type(config: &SourceType) -> anyhow::Result<impl SourceTypeConstructor> {
+fn get_test_type(config: &SourceType) -> Option<Box<dyn SourceTypeConstructor + '_>> {
     if let SourceType::Test(test) = config {
-        return Ok(test);
+        return Some(Box::new(test));
+    }
+    None
+}
+
+fn get_capture_type(config: &SourceType) -> Option<Box<dyn SourceTypeConstructor + '_>> {
+    if let SourceType::Capture(capture) = config {
+        return Some(Box::new(capture));
+    }
+    None
+}
+
+fn get_screen_type(config: &SourceType) -> Option<Box<dyn SourceTypeConstructor + '_>> {
+    if let SourceType::Screen(screen) = config {
+        return Some(Box::new(screen));
+    }
+    None
+}
+
+fn get_pipeline_type(config: &SourceType) -> Option<Box<dyn SourceTypeConstructor + '_>> {
+    if let SourceType::Pipeline(pipeline) = config {
+        return Some(Box::new(pipeline));
+    }
+    None
+}
+
+fn get_color_type(config: &SourceType) -> Option<Box<dyn SourceTypeConstructor + '_>> {
+    if let SourceType::Color(color) = config {
+        return Some(Box::new(color));
+    }
+    None
+}
+
+fn get_text_type(config: &SourceType) -> Option<Box<dyn SourceTypeConstructor + '_>> {
+    if let SourceType::Text(text) = config {
+        return Some(Box::new(text));
+    }
+    None
+}
+
+fn get_network_stream_type(config: &SourceType) -> Option<Box<dyn SourceTypeConstructor + '_>> {
+    if let SourceType::NetworkStream(stream) = config {
+        return Some(Box::new(stream));
+    }
+    None
+}
+
+fn get_image_sequence_type(config: &SourceType) -> Option<Box<dyn SourceTypeConstructor + '_>> {
+    if let SourceType::ImageSequence(sequence) = config {
+        return Some(Box::new(sequence));
+    }
+    None
+}
+
+fn get_shared_memory_type(config: &SourceType) -> Option<Box<dyn SourceTypeConstructor + '_>> {
+    if let SourceType::SharedMemory(shm) = config {
+        return Some(Box::new(shm));
+    }
+    None
+}
+
+/// Raw video caps restricted to an optional size and framerate
+fn video_caps(resolution: Option<&Resolution>, framerate: Option<u32>) -> gst::Caps {
+    let mut caps = gst_video::VideoCapsBuilder::new();
+    if let Some(resolution) = resolution {
+        caps = caps
+            .width(resolution.width as i32)
+            .height(resolution.height as i32);
+    }
+    if let Some(framerate) = framerate {
+        caps = caps.framerate(gst::Fraction::new(framerate as i32, 1));
+    }
+    caps.build()
+}
+
+/// Wraps a chain of elements in a bin whose src pad is the end of the chain
+fn source_bin(name: &str, elements: &[gst::Element]) -> Result<Element, glib::BoolError> {
+    let bin = gst::Bin::builder().name(name).build();
+    bin.add_many(elements)?;
+    gst::Element::link_many(elements)?;
+
+    let src_pad = elements
+        .last()
+        .and_then(|e| e.static_pad("src"))
+        .ok_or(glib::bool_error!("source {name} has no src pad"))?;
+    let ghost_pad = gst::GhostPad::with_target(&src_pad)?;
+    bin.add_pad(&ghost_pad)?;
+
+    Ok(bin.upcast())
+}
+
+/// Finds the element `{bin name}-{suffix}` inside a bin made by `source_bin`
+fn child_element(element: &gst::Element, suffix: &str) -> Result<gst::Element, glib::BoolError> {
+    let name = format!("{}-{}", element.name(), suffix);
+    element
+        .downcast_ref::<gst::Bin>()
+        .and_then(|bin| bin.by_name(&name))
+        .ok_or(glib::bool_error!("Source has no element {name}"))
+}
+
+/// Lists the nicks of an enum property on an element e.g. the patterns of `videotestsrc`
+pub fn enum_values(factory_name: &str, property: &str) -> Result<Vec<String>, glib::BoolError> {
+    let element = gst::ElementFactory::make(factory_name).build()?;
+    let pspec = element.find_property(property).ok_or(glib::bool_error!(
+        "{factory_name} has no property {property}"
+    ))?;
+    let enum_class = glib::EnumClass::with_type(pspec.value_type()).ok_or(glib::bool_error!(
+        "{factory_name}:{property} is not an enum"
+    ))?;
+
+    Ok(enum_class
+        .values()
+        .iter()
+        .map(|value| value.nick().to_string())
+        .collect())
+}
+
+/// Lists every device of a class known to the device monitor e.g. "Video/Source", none when the
+/// monitor can't start, e.g. on headless machines without any device provider
+pub fn monitor_devices(class: &str) -> Vec<gst::Device> {
+    let monitor = gst::DeviceMonitor::new();
+    monitor.add_filter(Some(class), None);
+    if let Err(err) = monitor.start() {
+        eprintln!("Failed to list {class} devices: {err}");
+        return vec![];
+    }
+    let devices = monitor.devices().into_iter().collect();
+    monitor.stop();
+    devices
+}
+
+/// Lists every video capture device known to the device monitor
+pub fn capture_devices() -> Vec<gst::Device> {
+    monitor_devices("Video/Source")
+}
+
+/// Lists every audio output known to the device monitor
+pub fn audio_output_devices() -> Vec<gst::Device> {
+    monitor_devices("Audio/Sink")
+}
+
+fn create_audio_sink(output: &AudioOutput) -> Result<gst::Element, glib::BoolError> {
+    match output {
+        AudioOutput::Auto => gst::ElementFactory::make("autoaudiosink").build(),
+        AudioOutput::Fake => gst::ElementFactory::make("fakesink")
+            .property("sync", true)
+            .build(),
+        AudioOutput::Device { name } => audio_output_devices()
+            .into_iter()
+            .find(|device| device.display_name().as_str() == name.as_str())
+            .ok_or(glib::bool_error!("Unknown audio output {name}"))?
+            .create_element(None),
+    }
+}
+
+/// The identifier used for `Capture::device`, the device path when the provider exposes one
+pub fn capture_device_id(device: &gst::Device) -> String {
+    device
+        .properties()
+        .and_then(|props| {
+            ["api.v4l2.path", "device.path"]
+                .iter()
+                .find_map(|key| props.get::<String>(*key).ok())
+        })
+        .unwrap_or_else(|| device.display_name().to_string())
+}
+
+fn find_capture_device(device_id: &str) -> Option<gst::Device> {
+    capture_devices().into_iter().find(|device| {
+        capture_device_id(device) == device_id || device.display_name().as_str() == device_id
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Lists itself with its version and builds nothing
+    struct Listed {
+        version: u32,
+    }
+
+    impl SourceKind for Listed {
+        fn name(&self) -> &str {
+            "listed"
+        }
+
+        fn constructor<'a>(
+            &self,
+            _source: &'a SourceType,
+        ) -> Option<Box<dyn SourceTypeConstructor + 'a>> {
+            None
+        }
+
+        fn options(&self) -> anyhow::Result<Option<SourceTypeOptions>> {
+            Ok(Some(SourceTypeOptions::Custom {
+                kind: self.name().to_owned(),
+                settings: serde_json::json!({ "version": self.version }),
+            }))
+        }
+    }
+
+    /// Can never list its options
+    struct Broken;
+
+    impl SourceKind for Broken {
+        fn name(&self) -> &str {
+            "broken"
+        }
+
+        fn constructor<'a>(
+            &self,
+            _source: &'a SourceType,
+        ) -> Option<Box<dyn SourceTypeConstructor + 'a>> {
+            None
+        }
+
+        fn options(&self) -> anyhow::Result<Option<SourceTypeOptions>> {
+            Err(anyhow::Error::msg("no devices"))
+        }
+    }
+
+    #[test]
+    fn overridden_kinds_are_listed_once() {
+        gst::init().unwrap();
+        register_source_kind(Listed { version: 1 });
+        register_source_kind(Listed { version: 2 });
+
+        let listed: Vec<serde_json::Value> = source_options()
+            .into_iter()
+            .filter_map(|option| match option {
+                SourceTypeOptions::Custom { kind, settings } if kind == "listed" => Some(settings),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(listed, vec![serde_json::json!({ "version": 2 })]);
+    }
+
+    #[test]
+    fn failing_kinds_are_left_out() {
+        gst::init().unwrap();
+        register_source_kind(Broken);
+
+        let options = source_options();
+        assert!(!options.is_empty());
+        assert!(!options.iter().any(
+            |option| matches!(option, SourceTypeOptions::Custom { kind, .. } if kind == "broken")
+        ));
     }
-    Err(anyhow::Error::msg("Could not find constructor Type"))
 }
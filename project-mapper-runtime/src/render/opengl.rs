@@ -5,9 +5,13 @@
 //! <https://github.com/rust-windowing/glutin/blob/master/glutin_examples/src/lib.rs>
 // {videotestsrc} - { glsinkbin }
 use std::{
+    cell::Cell,
     ffi::{CStr, CString},
     mem, ptr,
 };
+
+pub use project_mapper_core::config::sink::Crop;
+
 #[rustfmt::skip]
 static VERTICES: [f32; 20] = [
      1.0,  1.0, 0.0, 1.0, 0.0,
@@ -23,6 +27,14 @@ static IDENTITY: [f32; 16] = [
     0.0, 0.0, 1.0, 0.0,
     0.0, 0.0, 0.0, 1.0,
 ];
+// textures rendered by GL start at the bottom row, GStreamer's start at the top
+#[rustfmt::skip]
+static FLIP_Y: [f32; 16] = [
+    1.0,  0.0, 0.0, 0.0,
+    0.0, -1.0, 0.0, 0.0,
+    0.0,  0.0, 1.0, 0.0,
+    0.0,  0.0, 0.0, 1.0,
+];
 const VS_SRC: &[u8] = c"
 uniform mat4 u_transformation;
 attribute vec4 a_position;
@@ -53,8 +65,38 @@ pub(crate) mod gl {
     pub use self::Gles2 as Gl;
     include!(concat!(env!("OUT_DIR"), "/gl_bindings.rs"));
 }
+/// Offscreen framebuffer a sink's frames are drawn into at the canvas resolution
+struct Canvas {
+    framebuffer: gl::types::GLuint,
+    texture: gl::types::GLuint,
+    width: i32,
+    height: i32,
+}
+
+impl Canvas {
+    /// Largest viewport with the canvas aspect ratio centered on the surface
+    fn fit(&self, surface: (i32, i32)) -> (i32, i32, i32, i32) {
+        let scale = f64::min(
+            surface.0 as f64 / self.width as f64,
+            surface.1 as f64 / self.height as f64,
+        );
+        let width = (self.width as f64 * scale).round() as i32;
+        let height = (self.height as f64 * scale).round() as i32;
+        (
+            (surface.0 - width) / 2,
+            (surface.1 - height) / 2,
+            width,
+            height,
+        )
+    }
+}
+
 pub struct Gl {
     gl: gl::Gl,
+    canvas: Option<Canvas>,
+    // applied to the frame, scales the cropped part up to the whole viewport
+    transformation: [f32; 16],
+    surface_size: Cell<(i32, i32)>,
     program: gl::types::GLuint,
     attr_position: gl::types::GLint,
     attr_texture: gl::types::GLint,
@@ -64,20 +106,47 @@ pub struct Gl {
 }
 impl Gl {
     pub fn draw_frame(&self, texture_id: gl::types::GLuint) {
+        let Some(canvas) = &self.canvas else {
+            self.clear();
+            self.draw_texture(texture_id, &self.transformation);
+            return;
+        };
+        unsafe {
+            self.gl.BindFramebuffer(gl::FRAMEBUFFER, canvas.framebuffer);
+            self.gl.Viewport(0, 0, canvas.width, canvas.height);
+            self.clear();
+            self.draw_texture(texture_id, &self.transformation);
+
+            // letterbox the canvas onto the surface
+            self.gl.BindFramebuffer(gl::FRAMEBUFFER, 0);
+            let (width, height) = self.surface_size.get();
+            self.gl.Viewport(0, 0, width, height);
+            self.clear();
+            let (x, y, width, height) = canvas.fit((width, height));
+            self.gl.Viewport(x, y, width, height);
+            self.draw_texture(canvas.texture, &FLIP_Y);
+        }
+    }
+
+    fn clear(&self) {
         unsafe {
-            // render
             self.gl.ClearColor(0.0, 0.0, 0.0, 1.0);
             self.gl.Clear(gl::COLOR_BUFFER_BIT);
-            self.gl.BlendColor(0.0, 0.0, 0.0, 1.0);
+        }
+    }
+
+    fn draw_texture(&self, texture_id: gl::types::GLuint, transformation: &[f32; 16]) {
+        unsafe {
+            // straight alpha "over" the cleared background so transparent frames stay transparent
             if self.gl.BlendFuncSeparate.is_loaded() {
                 self.gl.BlendFuncSeparate(
                     gl::SRC_ALPHA,
-                    gl::CONSTANT_COLOR,
+                    gl::ONE_MINUS_SRC_ALPHA,
                     gl::ONE,
                     gl::ONE_MINUS_SRC_ALPHA,
                 );
             } else {
-                self.gl.BlendFunc(gl::SRC_ALPHA, gl::CONSTANT_COLOR);
+                self.gl.BlendFunc(gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA);
             }
             self.gl.BlendEquation(gl::FUNC_ADD);
             self.gl.Enable(gl::BLEND);
@@ -120,7 +189,7 @@ impl Gl {
                 .gl
                 .GetUniformLocation(self.program, c"u_transformation".as_ptr() as *const _);
             self.gl
-                .UniformMatrix4fv(location, 1, gl::FALSE, IDENTITY.as_ptr() as *const _);
+                .UniformMatrix4fv(location, 1, gl::FALSE, transformation.as_ptr() as *const _);
             self.gl
                 .DrawElements(gl::TRIANGLES, 6, gl::UNSIGNED_SHORT, ptr::null());
             self.gl.BindTexture(gl::TEXTURE_2D, 0);
@@ -137,10 +206,109 @@ impl Gl {
         }
     }
     pub fn resize(&self, size: winit::dpi::PhysicalSize<u32>) {
+        self.surface_size
+            .set((size.width as i32, size.height as i32));
+        // with a canvas the viewport is set for every frame
+        if self.canvas.is_none() {
+            unsafe {
+                self.gl
+                    .Viewport(0, 0, size.width as i32, size.height as i32);
+            }
+        }
+    }
+
+    /// Shows only `crop` of each frame, stretched over the whole surface
+    pub fn set_crop(&mut self, crop: Crop) {
+        // the quad spans -1..1, frame rows run top to bottom while y points up
+        let scale_x = 1.0 / crop.width;
+        let scale_y = 1.0 / crop.height;
+        let offset_x = (1.0 - 2.0 * crop.x - crop.width) / crop.width;
+        let offset_y = (crop.height - 1.0 + 2.0 * crop.y) / crop.height;
+        #[rustfmt::skip]
+        let transformation = [
+            scale_x,  0.0,      0.0, 0.0,
+            0.0,      scale_y,  0.0, 0.0,
+            0.0,      0.0,      1.0, 0.0,
+            offset_x, offset_y, 0.0, 1.0,
+        ];
+        self.transformation = transformation;
+    }
+
+    /// Draws frames at a fixed resolution, scaled to fit the surface, instead of at the surface size
+    pub fn set_canvas(&mut self, width: u32, height: u32) -> anyhow::Result<()> {
+        if width == 0 || height == 0 {
+            return Err(anyhow::Error::msg(format!(
+                "Canvas {width}x{height} has no area"
+            )));
+        }
         unsafe {
+            // the canvas is a single texture so it can't be larger than the driver allows
+            let mut max_size = 0;
+            self.gl.GetIntegerv(gl::MAX_TEXTURE_SIZE, &mut max_size);
+            if width.max(height) > max_size as u32 {
+                return Err(anyhow::Error::msg(format!(
+                    "Canvas {width}x{height} is larger than the maximum texture size {max_size}"
+                )));
+            }
+
+            let mut texture = mem::MaybeUninit::uninit();
+            self.gl.GenTextures(1, texture.as_mut_ptr());
+            let texture = texture.assume_init();
+            self.gl.BindTexture(gl::TEXTURE_2D, texture);
+            self.gl.TexImage2D(
+                gl::TEXTURE_2D,
+                0,
+                gl::RGBA as i32,
+                width as i32,
+                height as i32,
+                0,
+                gl::RGBA,
+                gl::UNSIGNED_BYTE,
+                ptr::null(),
+            );
+            self.gl
+                .TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::LINEAR as i32);
             self.gl
-                .Viewport(0, 0, size.width as i32, size.height as i32);
+                .TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as i32);
+            self.gl
+                .TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl::CLAMP_TO_EDGE as i32);
+            self.gl
+                .TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl::CLAMP_TO_EDGE as i32);
+            self.gl.BindTexture(gl::TEXTURE_2D, 0);
+
+            let mut framebuffer = mem::MaybeUninit::uninit();
+            self.gl.GenFramebuffers(1, framebuffer.as_mut_ptr());
+            let framebuffer = framebuffer.assume_init();
+            self.gl.BindFramebuffer(gl::FRAMEBUFFER, framebuffer);
+            self.gl.FramebufferTexture2D(
+                gl::FRAMEBUFFER,
+                gl::COLOR_ATTACHMENT0,
+                gl::TEXTURE_2D,
+                texture,
+                0,
+            );
+            let status = self.gl.CheckFramebufferStatus(gl::FRAMEBUFFER);
+            self.gl.BindFramebuffer(gl::FRAMEBUFFER, 0);
+            if status != gl::FRAMEBUFFER_COMPLETE {
+                self.gl.DeleteFramebuffers(1, &framebuffer);
+                self.gl.DeleteTextures(1, &texture);
+                return Err(anyhow::Error::msg(format!(
+                    "Canvas {width}x{height} framebuffer is incomplete ({status:#x})"
+                )));
+            }
+
+            if let Some(canvas) = self.canvas.take() {
+                self.gl.DeleteFramebuffers(1, &canvas.framebuffer);
+                self.gl.DeleteTextures(1, &canvas.texture);
+            }
+            self.canvas = Some(Canvas {
+                framebuffer: framebuffer,
+                texture: texture,
+                width: width as i32,
+                height: height as i32,
+            });
         }
+        Ok(())
     }
 }
 pub fn load(gl_display: &impl glutin::display::GlDisplay) -> Gl {
@@ -241,6 +409,9 @@ pub fn load(gl_display: &impl glutin::display::GlDisplay) -> Gl {
     };
     Gl {
         gl,
+        canvas: None,
+        transformation: IDENTITY,
+        surface_size: Cell::new((0, 0)),
         program,
         attr_position,
         attr_texture,
@@ -1,10 +1,18 @@
 use std::sync::mpsc;
 
+use gst::prelude::*;
+
 use project_mapper_core::config::{
     events::OptionEvent,
-    options::{AvailableConfig, RegionTypeOptions, SinkTypeOptions, SourceTypeOptions},
+    options::{
+        AvailableConfig, CaptureDeviceInfo, CaptureMode, RegionTypeOptions, SinkTypeOptions,
+        SourceTypeOptions,
+    },
+    sink::Resolution,
+    source::AudioOutput,
 };
 
+use crate::pipeline::{sink_constructor, source_constructor};
 use crate::window_handler::{self, config::ConfigHandler};
 
 use anyhow::Result;
@@ -13,17 +21,32 @@ pub fn generate_options() -> Result<AvailableConfig> {
     gst::init()?;
 
     let opengl_sink = generate_opengl_option()?;
-    let uri_source = generate_uri_option()?;
-    let test_source = SourceTypeOptions::Test {};
+    let file_sink = SinkTypeOptions::File {
+        formats: sink_constructor::recording_formats(),
+    };
+    let stream_sink = SinkTypeOptions::Stream {
+        protocols: sink_constructor::output_protocols(),
+    };
+    let shm_sink = gst::ElementFactory::find("shmsink").map(|_| SinkTypeOptions::SharedMemory {});
     let display_region = RegionTypeOptions::Display {};
+    let sources = generate_source_options()?;
+
+    let mut sinks = vec![opengl_sink, file_sink, stream_sink];
+    sinks.extend(shm_sink);
 
     Ok(AvailableConfig {
-        sinks: vec![opengl_sink],
-        sources: vec![uri_source, test_source],
+        sinks: sinks,
+        sources: sources,
         regions: vec![display_region],
     })
 }
 
+/// Options of every registered source kind, including those added with `register_source_kind`
+pub fn generate_source_options() -> Result<Vec<SourceTypeOptions>> {
+    gst::init()?;
+    Ok(source_constructor::source_options())
+}
+
 pub fn generate_opengl_option() -> Result<SinkTypeOptions> {
     let event_loop: winit::event_loop::EventLoop<window_handler::Message> =
         winit::event_loop::EventLoop::with_user_event().build()?;
@@ -61,7 +84,117 @@ pub fn generate_uri_option() -> Result<SourceTypeOptions> {
         }
     }
 
+    let mut audio_outputs = vec![AudioOutput::Auto, AudioOutput::Fake];
+    audio_outputs.extend(
+        source_constructor::audio_output_devices()
+            .iter()
+            .map(|device| AudioOutput::Device {
+                name: device.display_name().to_string(),
+            }),
+    );
+
     Ok(SourceTypeOptions::URI {
         uri_types: uri_types,
+        audio_outputs: audio_outputs,
+    })
+}
+
+pub fn generate_test_option() -> Result<SourceTypeOptions> {
+    let patterns = source_constructor::enum_values("videotestsrc", "pattern")?;
+
+    // only advertise overlays whose element is installed
+    let mut overlays = vec![String::from("None")];
+    for (overlay, factory) in [("Timecode", "timeoverlay"), ("Clock", "clockoverlay")] {
+        if gst::ElementFactory::find(factory).is_some() {
+            overlays.push(overlay.to_string());
+        }
+    }
+
+    Ok(SourceTypeOptions::Test {
+        patterns: patterns,
+        overlays: overlays,
+    })
+}
+
+pub fn generate_capture_option() -> Result<SourceTypeOptions> {
+    let mut devices = vec![];
+    for device in source_constructor::capture_devices() {
+        let mut modes = vec![];
+        if let Some(caps) = device.caps() {
+            for structure in caps.iter() {
+                let resolution = match (
+                    structure.get::<i32>("width"),
+                    structure.get::<i32>("height"),
+                ) {
+                    (Ok(width), Ok(height)) => Some(Resolution {
+                        width: width as u32,
+                        height: height as u32,
+                    }),
+                    _ => None,
+                };
+
+                // framerates are either fixed or a list depending on the driver
+                let mut framerates = vec![];
+                if let Ok(framerate) = structure.get::<gst::Fraction>("framerate") {
+                    framerates.push(framerate);
+                } else if let Ok(list) = structure.get::<gst::List>("framerate") {
+                    framerates.extend(list.iter().filter_map(|v| v.get::<gst::Fraction>().ok()));
+                }
+
+                modes.push(CaptureMode {
+                    media_type: structure.name().to_string(),
+                    format: structure.get::<String>("format").ok(),
+                    resolution: resolution,
+                    framerates: framerates
+                        .iter()
+                        .filter(|f| f.denom() != 0)
+                        .map(|f| (f.numer() / f.denom()) as u32)
+                        .collect(),
+                });
+            }
+        }
+
+        devices.push(CaptureDeviceInfo {
+            name: device.display_name().to_string(),
+            device: source_constructor::capture_device_id(&device),
+            modes: modes,
+        });
+    }
+
+    Ok(SourceTypeOptions::Capture { devices: devices })
+}
+
+pub fn generate_screen_option() -> Result<Option<SourceTypeOptions>> {
+    // screen capture is only available where ximagesrc is installed i.e. X11
+    if gst::ElementFactory::find("ximagesrc").is_none() {
+        return Ok(None);
+    }
+
+    Ok(Some(SourceTypeOptions::Screen {
+        areas: vec![
+            String::from("FullScreen"),
+            String::from("Region"),
+            String::from("Window"),
+        ],
+    }))
+}
+
+pub fn generate_network_stream_option() -> Result<SourceTypeOptions> {
+    let mut protocols = vec![];
+    for (protocol, factories) in [
+        ("Rtsp", vec!["rtspsrc"]),
+        ("Srt", vec!["srtsrc"]),
+        ("Rtp", vec!["udpsrc", "rtpjitterbuffer"]),
+    ] {
+        if factories
+            .iter()
+            .all(|factory| gst::ElementFactory::find(factory).is_some())
+        {
+            protocols.push(protocol.to_string());
+        }
+    }
+
+    Ok(SourceTypeOptions::NetworkStream {
+        protocols: protocols,
     })
 }
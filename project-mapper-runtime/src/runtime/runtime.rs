@@ -8,7 +8,9 @@
 
 use std::sync::{Arc, Mutex};
 use std::thread;
+use std::time::Duration;
 
+use crate::api::control::ControlServer;
 use crate::{pipeline, window_handler};
 
 use anyhow::Result;
@@ -22,46 +24,77 @@ pub(crate) struct Runtime {
     event_sender: mpsc::Sender<events::RuntimeEvent>,
     event_recver: Arc<Mutex<mpsc::Receiver<events::RuntimeEvent>>>,
     event_thread: Option<thread::JoinHandle<()>>,
-    window_handler: window_handler::WindowHandler,
+    window_handler: Option<window_handler::WindowHandler>,
+    control_address: Option<String>,
+    duration: Option<Duration>,
 }
 
 impl Runtime {
-    pub(crate) fn new(config: runtime::RuntimeConfig) -> Result<Runtime> {
+    pub(crate) fn new(
+        config: runtime::RuntimeConfig,
+        control_address: Option<String>,
+        duration: Option<Duration>,
+    ) -> Result<Runtime> {
         gst::init()?;
 
-        let event_loop: winit::event_loop::EventLoop<window_handler::Message> =
-            winit::event_loop::EventLoop::with_user_event().build()?;
-
-        // ControlFlow::Wait pauses the event loop if no events are available to process.
-        // This is ideal for non-game applications that only update in response to user
-        // input, and uses significantly less power/CPU time than ControlFlow::Poll.
-        event_loop.set_control_flow(winit::event_loop::ControlFlow::Wait);
-
         let (send, recv) = mpsc::channel();
 
-        let mut window_handler =
-            window_handler::WindowHandler::new(event_loop.create_proxy(), send.clone());
+        // only open a display connection when something is shown, so recordings run headless
+        let headless = !config.sinks.iter().any(|sink| sink.sink.needs_window());
+        let (event_loop, mut window_handler) = if headless {
+            println!("no sink needs a window, running headless");
+            (None, None)
+        } else {
+            let event_loop: winit::event_loop::EventLoop<window_handler::Message> =
+                winit::event_loop::EventLoop::with_user_event().build()?;
+
+            // ControlFlow::Wait pauses the event loop if no events are available to process.
+            // This is ideal for non-game applications that only update in response to user
+            // input, and uses significantly less power/CPU time than ControlFlow::Poll.
+            event_loop.set_control_flow(winit::event_loop::ControlFlow::Wait);
+
+            let window_handler =
+                window_handler::WindowHandler::new(event_loop.create_proxy(), send.clone());
+            (Some(event_loop), Some(window_handler))
+        };
 
-        let media_pipeline =
-            pipeline::MediaPipeline::new(config, &mut window_handler, &event_loop, send.clone())?;
+        let windows = window_handler.as_mut().zip(event_loop.as_ref());
+        let media_pipeline = pipeline::MediaPipeline::new(config, windows, send.clone())?;
 
         let runtime = Runtime {
             pipeline: media_pipeline,
             event_recver: Arc::new(Mutex::new(recv)),
             event_sender: send,
             event_thread: None,
-            event_loop: Some(event_loop),
+            event_loop: event_loop,
             window_handler: window_handler,
+            control_address: control_address,
+            duration: duration,
         };
         Ok(runtime)
     }
 
     pub fn run(&mut self) -> Result<()> {
         let event_thread = self.start_background_thread()?;
+        if let Some(address) = &self.control_address {
+            ControlServer::new(self.pipeline.pipeline.clone()).start(address)?;
+        }
 
-        self.pipeline.start();
-        let event_loop = std::mem::replace(&mut self.event_loop, None).expect("uh oh");
-        event_loop.run_app(&mut self.window_handler);
+        self.pipeline.start()?;
+        if let Some(duration) = self.duration {
+            let event_sender = self.event_sender.clone();
+            thread::spawn(move || {
+                thread::sleep(duration);
+                let _ = event_sender.send(events::RuntimeEvent::StopThread());
+            });
+        }
+
+        // headless runs end when the event thread stops the pipeline
+        if let (Some(event_loop), Some(window_handler)) =
+            (self.event_loop.take(), self.window_handler.as_mut())
+        {
+            event_loop.run_app(window_handler);
+        }
 
         event_thread.join();
         Ok(())
@@ -70,9 +103,14 @@ impl Runtime {
     fn start_background_thread(&self) -> Result<thread::JoinHandle<()>> {
         let recv: Arc<Mutex<mpsc::Receiver<events::RuntimeEvent>>> = self.event_recver.clone();
         let pipeline = self.pipeline.pipeline.clone();
+        // the event loop only ends by itself when a window is closed
+        let event_proxy = self
+            .event_loop
+            .as_ref()
+            .map(|event_loop| event_loop.create_proxy());
 
         let background_thread = thread::spawn(move || {
-            Runtime::listen_for_events(recv, pipeline);
+            Runtime::listen_for_events(recv, pipeline, event_proxy);
         });
         return Ok(background_thread);
     }
@@ -80,6 +118,7 @@ impl Runtime {
     fn listen_for_events(
         event_recver: Arc<Mutex<mpsc::Receiver<events::RuntimeEvent>>>,
         pipeline: gst::Pipeline,
+        event_proxy: Option<winit::event_loop::EventLoopProxy<window_handler::Message>>,
     ) {
         for event in event_recver.lock().unwrap().iter() {
             match event {
@@ -91,8 +130,42 @@ impl Runtime {
                 events::RuntimeEvent::StopThread() => {
                     println!("Official stop");
                     pipeline::MediaPipeline::shutdown_pipeline(pipeline);
+                    if let Some(event_proxy) = &event_proxy {
+                        let _ = event_proxy.send_event(window_handler::Message::Exit);
+                    }
                     break;
                 }
+                events::RuntimeEvent::SourceFailed { id, message } => {
+                    eprintln!("Source {id} failed, showing its fallback: {message}");
+                }
+                events::RuntimeEvent::SourceRetrying {
+                    id,
+                    attempt,
+                    delay_ms,
+                } => {
+                    println!("Retrying source {id} in {delay_ms}ms (attempt {attempt})");
+                }
+                events::RuntimeEvent::SourceGaveUp { id, attempts } => {
+                    eprintln!("Giving up on source {id} after {attempts} retries");
+                }
+                events::RuntimeEvent::SourceRecovered { id } => {
+                    println!("Source {id} recovered");
+                }
+                events::RuntimeEvent::MonitorConnected { name } => {
+                    println!("Monitor {name} connected");
+                }
+                events::RuntimeEvent::MonitorDisconnected { name } => {
+                    eprintln!("Monitor {name} disconnected");
+                }
+                events::RuntimeEvent::SinkMonitorChanged { sink, monitor } => {
+                    println!("Sink {sink} is on monitor {monitor}");
+                }
+                events::RuntimeEvent::SinkMonitorMissing { sink, message } => {
+                    eprintln!("Sink {sink} lost its monitor: {message}");
+                }
+                events::RuntimeEvent::PipelineError { element, message } => {
+                    eprintln!("Received error from {element}: {message}");
+                }
             }
         }
     }
@@ -4,7 +4,8 @@ use std::sync::mpsc;
 use gst::prelude::PadExt;
 use project_mapper_core::config::events;
 use project_mapper_core::config::options::{
-    BorderlessOptions, ExclusiveOptions, FullscreenOptions, SinkTypeOptions, WindowOptions,
+    BorderlessOptions, ExclusiveOptions, FullscreenOptions, OutputOptions, SinkTypeOptions,
+    WindowOptions,
 };
 use project_mapper_core::config::sink::{RefreshRate, ResolutionJson};
 use winit::application::ApplicationHandler;
@@ -26,11 +27,11 @@ impl ConfigHandler {
 
 impl ApplicationHandler<Message> for ConfigHandler {
     fn resumed(&mut self, event_loop: &ActiveEventLoop) {
-        let monitor_map = super::utils::gather_monitor_info(event_loop);
+        let monitors = super::utils::gather_monitor_info(event_loop);
 
         let mut monitor_configs: HashMap<String, HashMap<ResolutionJson, Vec<RefreshRate>>> =
             HashMap::new();
-        for (monitor_name, monitor_data) in &monitor_map {
+        for monitor_data in &monitors {
             let mut resolution_map = HashMap::new();
             for (resolution, refresh_rate_map) in &monitor_data.mode_lookup {
                 let mut refresh_rates: Vec<RefreshRate> = Vec::new();
@@ -39,7 +40,7 @@ impl ApplicationHandler<Message> for ConfigHandler {
                 }
                 resolution_map.insert(resolution.clone(), refresh_rates);
             }
-            monitor_configs.insert(monitor_name.clone(), resolution_map);
+            monitor_configs.insert(monitor_data.name.clone(), resolution_map);
         }
 
         let mut monitor_names = Vec::new();
@@ -55,10 +56,18 @@ impl ApplicationHandler<Message> for ConfigHandler {
             FullscreenOptions::Borderless(BorderlessOptions {
                 monitors: monitor_names,
             }),
+            FullscreenOptions::Output(OutputOptions {}),
         ];
 
+        // already in index order
+        let descriptors = monitors
+            .iter()
+            .map(|monitor_data| monitor_data.descriptor.clone())
+            .collect();
+
         let sink_options = SinkTypeOptions::OpenGLWindow {
             full_screen_modes: fullscreen_options,
+            monitors: descriptors,
         };
 
         self.event_sender
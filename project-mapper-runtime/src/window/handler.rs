@@ -1,8 +1,11 @@
 use std::collections::HashMap;
 use std::num::NonZeroU32;
-use std::sync::mpsc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, mpsc};
+use std::time::{Duration, Instant};
 
 use crate::opengl::{self, gl};
+use crate::pipeline::MediaPipeline;
 use anyhow::{Context, Error, Result};
 use glutin::config::{GetGlConfig, GlConfig};
 use glutin::context::AsRawContext;
@@ -16,14 +19,15 @@ use gst_gl::prelude::GLContextExt;
 use gst_gl::{GLPlatform, GLVideoFrameExt};
 use gst_video::VideoFrameExt;
 use project_mapper_core::config::events;
+use project_mapper_core::config::sink::{MonitorDescriptor, WindowGeometry};
 use raw_window_handle::HasWindowHandle;
 use winit::application::ApplicationHandler;
-use winit::dpi::PhysicalSize;
+use winit::dpi::{PhysicalPosition, PhysicalSize};
 use winit::event::WindowEvent;
 use winit::event_loop::ActiveEventLoop;
 use winit::window::{Window, WindowId};
 
-use super::utils::MonitorData;
+use super::utils::{MonitorData, MonitorLayout, find_monitor};
 
 struct WindowData {
     window: Window,
@@ -35,6 +39,14 @@ struct WindowData {
     not_current_gl_context: Option<glutin::context::NotCurrentContext>,
     glutin_context: gst_gl::GLContext,
     config: project_mapper_core::config::sink::SinkType,
+    name: String,
+    // part of the frames the window shows, for the tiles of a span
+    crop: Option<opengl::Crop>,
+    // monitor the window was last put on
+    monitor: Option<MonitorDescriptor>,
+    appsink: gst_app::AppSink,
+    // a FrameReady is on its way, so the appsink only wakes the event loop once
+    frame_pending: Arc<AtomicBool>,
 }
 
 impl WindowData {
@@ -54,6 +66,46 @@ impl WindowData {
         }
     }
 
+    /// Pulls the oldest queued frame and readies it for drawing in the window's GL context
+    fn next_frame(&self) -> Option<gst_gl::GLVideoFrame<gst_gl::gl_video_frame::Readable>> {
+        let appsink = &self.appsink;
+        let sample = appsink.try_pull_sample(gst::ClockTime::ZERO)?;
+        let Some(info) = sample
+            .caps()
+            .and_then(|caps| gst_video::VideoInfo::from_caps(caps).ok())
+        else {
+            element_error!(
+                appsink,
+                gst::ResourceError::Failed,
+                ("Failed to get video info from sample")
+            );
+            return None;
+        };
+        let mut buffer = sample.buffer_owned()?;
+        {
+            let Some(context) = (buffer.n_memory() > 0)
+                .then(|| buffer.peek_memory(0))
+                .and_then(|m| m.downcast_memory_ref::<gst_gl::GLBaseMemory>())
+                .map(|m| m.context().clone())
+            else {
+                element_error!(
+                    appsink,
+                    gst::ResourceError::Failed,
+                    ("Failed to get GL context from buffer")
+                );
+                return None;
+            };
+            if let Some(meta) = buffer.meta::<gst_gl::GLSyncMeta>() {
+                meta.set_sync_point(&context);
+            } else {
+                let buffer = buffer.make_mut();
+                let meta = gst_gl::GLSyncMeta::add(buffer, &context);
+                meta.set_sync_point(&context);
+            }
+        }
+        gst_gl::GLVideoFrame::from_buffer_readable(buffer, &info).ok()
+    }
+
     pub fn resize(&self, size: PhysicalSize<u32>) {
         if let Some((gl, gl_context, gl_surface)) = &self.running_state {
             gl_context
@@ -73,14 +125,25 @@ impl WindowData {
 
 #[derive(Debug)]
 pub(crate) enum Message {
-    Frame(gst_video::VideoInfo, gst::Buffer, WindowId),
+    // the window's appsink has a frame queued
+    FrameReady(WindowId),
     BusMessage(gst::Message),
+    // the runtime stopped on its own, e.g. after --duration-s
+    Exit,
 }
 
+// winit has no event for monitors coming and going, they are polled instead
+const MONITOR_POLL_INTERVAL_MS: u64 = 1000;
+
 pub struct WindowHandler {
     windows: HashMap<WindowId, WindowData>,
     event_proxy: winit::event_loop::EventLoopProxy<Message>,
     event_sender: mpsc::Sender<events::RuntimeEvent>,
+    // the connected monitors from left to right, None until the event loop resumed
+    monitors: Option<Vec<MonitorDescriptor>>,
+    // what the monitors looked like at the last poll, video modes are only read when it changes
+    monitor_layout: Vec<MonitorLayout>,
+    next_monitor_poll: Instant,
 }
 
 impl WindowHandler {
@@ -92,6 +155,9 @@ impl WindowHandler {
             windows: HashMap::new(),
             event_proxy: event_proxy,
             event_sender: event_sender,
+            monitors: None,
+            monitor_layout: Vec::new(),
+            next_monitor_poll: Instant::now(),
         }
     }
 
@@ -101,63 +167,34 @@ impl WindowHandler {
         appsink: gst_app::AppSink,
         event_loop: &winit::event_loop::EventLoop<Message>,
         sink_info: project_mapper_core::config::sink::SinkType,
+        crop: Option<opengl::Crop>,
     ) {
         let event_proxy = self.event_proxy.clone();
         let appsink_id = sink_name.clone();
 
-        let window_data = self
+        let mut window_data = self
             .create_window(appsink_id.clone(), &appsink, event_loop, sink_info)
             .expect("we get a result");
+        window_data.crop = crop;
         let window_id = window_data.window.id();
 
+        // frames stay queued in the appsink until the event loop pulls them, so its max-buffers
+        // and drop settings decide what happens when the window falls behind
+        let frame_pending = window_data.frame_pending.clone();
         appsink.set_callbacks(
             gst_app::AppSinkCallbacks::builder()
                 .new_sample(move |appsink| {
-                    let sample = appsink.pull_sample().map_err(|_| gst::FlowError::Eos)?;
-                    let info = sample
-                        .caps()
-                        .and_then(|caps| gst_video::VideoInfo::from_caps(caps).ok())
-                        .ok_or_else(|| {
-                            element_error!(
-                                appsink,
-                                gst::ResourceError::Failed,
-                                ("Failed to get video info from sample")
-                            );
-                            gst::FlowError::NotNegotiated
-                        })?;
-                    let mut buffer = sample.buffer_owned().unwrap();
-                    {
-                        let context = match (buffer.n_memory() > 0)
-                            .then(|| buffer.peek_memory(0))
-                            .and_then(|m| m.downcast_memory_ref::<gst_gl::GLBaseMemory>())
-                            .map(|m| m.context())
-                        {
-                            Some(context) => context.clone(),
-                            None => {
-                                element_error!(
-                                    appsink,
-                                    gst::ResourceError::Failed,
-                                    ("Failed to get GL context from buffer")
-                                );
-                                return Err(gst::FlowError::Error);
-                            }
-                        };
-                        if let Some(meta) = buffer.meta::<gst_gl::GLSyncMeta>() {
-                            meta.set_sync_point(&context);
-                        } else {
-                            let buffer = buffer.make_mut();
-                            let meta = gst_gl::GLSyncMeta::add(buffer, &context);
-                            meta.set_sync_point(&context);
-                        }
+                    if frame_pending.swap(true, Ordering::SeqCst) {
+                        return Ok(gst::FlowSuccess::Ok);
                     }
                     event_proxy
-                        .send_event(Message::Frame(info, buffer, window_id))
+                        .send_event(Message::FrameReady(window_id))
                         .map(|()| gst::FlowSuccess::Ok)
                         .map_err(|e| {
                             element_error!(
                                 appsink,
                                 gst::ResourceError::Failed,
-                                ("Failed to send sample to event loop: {}", e)
+                                ("Failed to wake the event loop: {}", e)
                             );
                             gst::FlowError::Error
                         })
@@ -175,9 +212,16 @@ impl WindowHandler {
         event_loop: &winit::event_loop::EventLoop<Message>,
         sink_info: project_mapper_core::config::sink::SinkType,
     ) -> Result<WindowData> {
-        let window_attributes = winit::window::Window::default_attributes()
+        let mut window_attributes = winit::window::Window::default_attributes()
             .with_transparent(true)
             .with_title(name.clone().to_string());
+        if let project_mapper_core::config::sink::SinkType::OpenGLWindow {
+            full_screen: project_mapper_core::config::sink::FullScreenMode::Windowed(geometry),
+            ..
+        } = &sink_info
+        {
+            window_attributes = WindowHandler::apply_geometry(window_attributes, geometry);
+        }
 
         let template = glutin::config::ConfigTemplateBuilder::new().with_alpha_size(8);
 
@@ -339,14 +383,42 @@ impl WindowHandler {
             not_current_gl_context: Some(not_current_gl_context),
             glutin_context: glutin_context,
             config: sink_info,
+            name: name.to_string(),
+            crop: None,
+            monitor: None,
+            appsink: appsink.clone(),
+            frame_pending: Arc::new(AtomicBool::new(false)),
         };
 
         Ok(window_data)
     }
 
+    /// Applies everything that doesn't need to know the monitors, those are only known once the
+    /// event loop runs so positions relative to a monitor are set in `configure_fullscreen`
+    fn apply_geometry(
+        mut window_attributes: winit::window::WindowAttributes,
+        geometry: &WindowGeometry,
+    ) -> winit::window::WindowAttributes {
+        window_attributes = window_attributes.with_decorations(geometry.decorations);
+        if geometry.always_on_top {
+            window_attributes =
+                window_attributes.with_window_level(winit::window::WindowLevel::AlwaysOnTop);
+        }
+        if let Some(size) = &geometry.size {
+            window_attributes =
+                window_attributes.with_inner_size(PhysicalSize::new(size.width, size.height));
+        }
+        if let (None, Some(position)) = (&geometry.monitor, &geometry.position) {
+            window_attributes =
+                window_attributes.with_position(PhysicalPosition::new(position.x, position.y));
+        }
+        window_attributes
+    }
+
     fn configure_running_window(
         window_data: &mut WindowData,
-        monitor_data: &HashMap<String, MonitorData>,
+        monitor_data: &[MonitorData],
+        event_sender: &mpsc::Sender<events::RuntimeEvent>,
     ) {
         let not_current_gl_context = window_data
             .not_current_gl_context
@@ -356,9 +428,14 @@ impl WindowHandler {
         let gl_config = not_current_gl_context.config();
         let gl_display = gl_config.display();
 
-        //window.set_fullscreen(Some(winit::window::Fullscreen::Borderless(event_loop.primary_monitor())));
-        WindowHandler::configure_fullscreen(window_data, monitor_data)
-            .expect("you did bad cause error");
+        // the window stays where it was created until poll_monitors finds its monitor
+        if let Err(err) = WindowHandler::configure_fullscreen(window_data, monitor_data) {
+            let _ = event_sender.send(events::RuntimeEvent::SinkMonitorMissing {
+                sink: window_data.name.clone(),
+                message: err.to_string(),
+            });
+        }
+        WindowHandler::configure_kiosk(window_data);
 
         let attrs = window_data
             .window
@@ -381,13 +458,32 @@ impl WindowHandler {
             .expect("Couldn't fill context info");
         // The context needs to be current for the Renderer to set up shaders and buffers.
         // It also performs function loading, which needs a current context on WGL.
-        let gl = opengl::load(&gl_display);
+        let mut gl = opengl::load(&gl_display);
+        if let project_mapper_core::config::sink::SinkType::OpenGLWindow {
+            canvas: Some(canvas),
+            ..
+        } = &window_data.config
+        {
+            // frames are still shown at the window size without the canvas
+            if let Err(err) = gl.set_canvas(canvas.width, canvas.height) {
+                eprintln!("Sink {} can't use its canvas: {err}", window_data.name);
+            }
+        }
+        if let Some(crop) = window_data.crop {
+            gl.set_crop(crop);
+        }
+        gl.resize(window_data.window.inner_size());
 
         // Try setting vsync.
-        if let Err(res) = gl_surface.set_swap_interval(
-            &gl_context,
-            glutin::surface::SwapInterval::Wait(std::num::NonZeroU32::new(1).unwrap()),
-        ) {
+        let pacing = window_data.config.pacing().cloned().unwrap_or_default();
+        let swap_interval = if pacing.vsync {
+            glutin::surface::SwapInterval::Wait(
+                std::num::NonZeroU32::new(pacing.swap_interval.max(1)).unwrap(),
+            )
+        } else {
+            glutin::surface::SwapInterval::DontWait
+        };
+        if let Err(res) = gl_surface.set_swap_interval(&gl_context, swap_interval) {
             eprintln!("Error setting vsync: {res:?}");
         }
 
@@ -395,86 +491,240 @@ impl WindowHandler {
     }
 
     /// Should be called from within the event loop
-    fn handle_message(msg: gst::Message) {
-        use gst::MessageView;
-        // Only handle error messages by panicking, to hard-stop the event loop
-        if let MessageView::Error(err) = msg.view() {
-            let src = msg
-                .src()
-                .map(|s| s.path_string())
-                .unwrap_or_else(|| glib::GString::from("UNKNOWN"));
-            let error = err.error();
-            let debug = err.debug();
-            panic!("Received error from {src}: {error} (debug: {debug:?})");
+    fn handle_message(&self, msg: gst::Message) {
+        MediaPipeline::report_error(&msg, &self.event_sender);
+    }
+
+    /// Hides the cursor, raises the window and holds off the screensaver as the sink asks
+    fn configure_kiosk(window_data: &WindowData) {
+        let project_mapper_core::config::sink::SinkType::OpenGLWindow { kiosk, .. } =
+            &window_data.config
+        else {
+            return;
+        };
+        if kiosk.hide_cursor {
+            window_data.window.set_cursor_visible(false);
+        }
+        if kiosk.always_on_top {
+            window_data
+                .window
+                .set_window_level(winit::window::WindowLevel::AlwaysOnTop);
+        }
+        if kiosk.inhibit_screensaver {
+            if let Err(err) = super::utils::inhibit_screensaver(&window_data.window) {
+                eprintln!(
+                    "Could not inhibit the screensaver for {}: {err}",
+                    window_data.name
+                );
+            }
         }
     }
 
+    /// The monitor the sink's full screen mode asks for, None for windows placed on the desktop
+    fn wanted_monitor<'a>(
+        config: &project_mapper_core::config::sink::SinkType,
+        monitor_data: &'a [MonitorData],
+    ) -> Result<Option<&'a MonitorData>> {
+        let project_mapper_core::config::sink::SinkType::OpenGLWindow {
+            full_screen,
+            monitor: monitor_match,
+            ..
+        } = config
+        else {
+            return Err(Error::msg("Only OpenGL window sinks have a window"));
+        };
+        let name = match full_screen {
+            project_mapper_core::config::sink::FullScreenMode::Borderless { name } => name,
+            project_mapper_core::config::sink::FullScreenMode::Exclusive { info } => &info.name,
+            project_mapper_core::config::sink::FullScreenMode::Windowed(geometry) => {
+                match &geometry.monitor {
+                    Some(name) => name,
+                    None => return Ok(None),
+                }
+            }
+            project_mapper_core::config::sink::FullScreenMode::Output { name } => {
+                return Err(Error::msg(format!(
+                    "Output {name} was not mapped to a monitor by a venue profile"
+                )));
+            }
+        };
+        find_monitor(name, monitor_match.as_ref(), monitor_data)
+            .map(Some)
+            .ok_or_else(|| {
+                let monitor_names: Vec<&String> =
+                    monitor_data.iter().map(|monitor| &monitor.name).collect();
+                Error::msg(format!(
+                    "Unkown monitor name {name} supported monitors: {monitor_names:?}"
+                ))
+            })
+    }
+
     fn configure_fullscreen(
         window_data: &mut WindowData,
-        monitor_data: &HashMap<String, MonitorData>,
+        monitor_data: &[MonitorData],
     ) -> Result<()> {
-        match &window_data.config {
-            project_mapper_core::config::sink::SinkType::OpenGLWindow { full_screen } => {
-                match full_screen {
-                    project_mapper_core::config::sink::FullScreenMode::Borderless { name } => {
-                        match &monitor_data.get(name) {
-                            None => {
-                                let monitor_names = monitor_data.keys();
-                                Err(Error::msg(format!(
-                                    "Unkown monitor name {name} supported monitors: {monitor_names:?}"
-                                )))
-                            }
-                            Some(monitor) => {
-                                window_data.window.set_fullscreen(Some(
-                                    winit::window::Fullscreen::Borderless(Some(
-                                        monitor.monitor.clone(),
-                                    )),
-                                ));
-                                Ok(())
-                            }
-                        }
+        let monitor = WindowHandler::wanted_monitor(&window_data.config, monitor_data)?;
+        let project_mapper_core::config::sink::SinkType::OpenGLWindow { full_screen, .. } =
+            &window_data.config
+        else {
+            return Err(Error::msg("Only OpenGL window sinks have a window"));
+        };
+        match (full_screen, monitor) {
+            (
+                project_mapper_core::config::sink::FullScreenMode::Borderless { .. },
+                Some(monitor),
+            ) => {
+                window_data
+                    .window
+                    .set_fullscreen(Some(winit::window::Fullscreen::Borderless(Some(
+                        monitor.monitor.clone(),
+                    ))));
+            }
+            (
+                project_mapper_core::config::sink::FullScreenMode::Exclusive { info },
+                Some(monitor),
+            ) => {
+                let video_mode = monitor
+                    .mode_lookup
+                    .get(&info.resolution)
+                    .ok_or(Error::msg("unknown resolution"))?
+                    .get(&info.refresh_rate_hz)
+                    .ok_or(Error::msg("unknown refresh rate"))?;
+                window_data
+                    .window
+                    .set_fullscreen(Some(winit::window::Fullscreen::Exclusive(
+                        video_mode.clone(),
+                    )));
+            }
+            (
+                project_mapper_core::config::sink::FullScreenMode::Windowed(geometry),
+                Some(monitor),
+            ) => {
+                let origin = monitor.monitor.position();
+                let offset = geometry.position.clone().unwrap_or_default();
+                window_data.window.set_outer_position(PhysicalPosition::new(
+                    origin.x + offset.x,
+                    origin.y + offset.y,
+                ));
+            }
+            // a window placed on the desktop, outputs never get this far
+            (project_mapper_core::config::sink::FullScreenMode::Output { .. }, _) | (_, None) => {}
+        }
+        window_data.monitor = monitor.map(WindowHandler::monitor_identity);
+        Ok(())
+    }
+
+    /// What tells a monitor apart between polls, the index shifts whenever a monitor left of it
+    /// comes or goes so it is left out
+    fn monitor_identity(monitor: &MonitorData) -> MonitorDescriptor {
+        MonitorDescriptor {
+            index: None,
+            ..monitor.descriptor.clone()
+        }
+    }
+
+    /// Re-applies the full screen mode of windows whose monitor went away or came back
+    fn poll_monitors(&mut self, event_loop: &ActiveEventLoop) {
+        let layout = super::utils::monitor_layout(event_loop);
+        if layout == self.monitor_layout {
+            return;
+        }
+        self.monitor_layout = layout;
+
+        let monitor_data = super::utils::gather_monitor_info(event_loop);
+        // a monitor that moved counts as a new one, names aren't unique
+        let descriptors: Vec<MonitorDescriptor> = monitor_data
+            .iter()
+            .map(WindowHandler::monitor_identity)
+            .collect();
+        let Some(known) = self.monitors.replace(descriptors.clone()) else {
+            return;
+        };
+        if known == descriptors {
+            return;
+        }
+
+        for descriptor in descriptors.iter().filter(|found| !known.contains(found)) {
+            let _ = self
+                .event_sender
+                .send(events::RuntimeEvent::MonitorConnected {
+                    name: descriptor.name.clone(),
+                });
+        }
+        for descriptor in known.iter().filter(|known| !descriptors.contains(known)) {
+            let _ = self
+                .event_sender
+                .send(events::RuntimeEvent::MonitorDisconnected {
+                    name: descriptor.name.clone(),
+                });
+        }
+
+        for window_data in self.windows.values_mut() {
+            if window_data.running_state.is_none() {
+                continue;
+            }
+            let wanted = WindowHandler::wanted_monitor(&window_data.config, &monitor_data)
+                .map(|monitor| monitor.map(WindowHandler::monitor_identity));
+            let event = match wanted {
+                // already where it should be
+                Ok(wanted) if wanted == window_data.monitor => continue,
+                Ok(_) => match WindowHandler::configure_fullscreen(window_data, &monitor_data) {
+                    Ok(()) => events::RuntimeEvent::SinkMonitorChanged {
+                        sink: window_data.name.clone(),
+                        monitor: window_data
+                            .monitor
+                            .as_ref()
+                            .map(|monitor| monitor.name.clone())
+                            .unwrap_or_default(),
+                    },
+                    Err(err) => events::RuntimeEvent::SinkMonitorMissing {
+                        sink: window_data.name.clone(),
+                        message: err.to_string(),
+                    },
+                },
+                Err(err) => {
+                    // report a lost monitor once, it is applied again when it returns
+                    if window_data.monitor.take().is_none() {
+                        continue;
                     }
-                    project_mapper_core::config::sink::FullScreenMode::Exclusive { info } => {
-                        match &monitor_data.get(&info.name) {
-                            None => {
-                                let attempted_name = &info.name;
-                                let monitor_names = monitor_data.keys();
-                                Err(Error::msg(format!(
-                                    "Unkown monitor name {attempted_name} supported monitors: {monitor_names:?}"
-                                )))
-                            }
-                            Some(monitor) => {
-                                let video_mode = monitor
-                                    .mode_lookup
-                                    .get(&info.resolution)
-                                    .ok_or(Error::msg("unknown resolution"))?
-                                    .get(&info.refresh_rate_hz)
-                                    .ok_or(Error::msg("unknown refresh rate"))?;
-                                window_data.window.set_fullscreen(Some(
-                                    winit::window::Fullscreen::Exclusive(video_mode.clone()),
-                                ));
-                                Ok(())
-                            }
-                        }
+                    events::RuntimeEvent::SinkMonitorMissing {
+                        sink: window_data.name.clone(),
+                        message: err.to_string(),
                     }
-                    project_mapper_core::config::sink::FullScreenMode::Windowed {} => Ok(()),
                 }
-            }
+            };
+            let _ = self.event_sender.send(event);
         }
     }
 }
 
 impl ApplicationHandler<Message> for WindowHandler {
     fn resumed(&mut self, event_loop: &ActiveEventLoop) {
+        self.monitor_layout = super::utils::monitor_layout(event_loop);
         let monitor_data = super::utils::gather_monitor_info(event_loop);
         for (_, windows) in self.windows.iter_mut() {
-            WindowHandler::configure_running_window(windows, &monitor_data);
+            WindowHandler::configure_running_window(windows, &monitor_data, &self.event_sender);
         }
+        self.monitors = Some(
+            monitor_data
+                .iter()
+                .map(WindowHandler::monitor_identity)
+                .collect(),
+        );
     }
 
-    fn window_event(&mut self, event_loop: &ActiveEventLoop, id: WindowId, event: WindowEvent) {
-        event_loop.set_control_flow(winit::event_loop::ControlFlow::Wait);
+    fn about_to_wait(&mut self, event_loop: &ActiveEventLoop) {
+        if Instant::now() >= self.next_monitor_poll {
+            self.poll_monitors(event_loop);
+            self.next_monitor_poll =
+                Instant::now() + Duration::from_millis(MONITOR_POLL_INTERVAL_MS);
+        }
+        event_loop.set_control_flow(winit::event_loop::ControlFlow::WaitUntil(
+            self.next_monitor_poll,
+        ));
+    }
 
+    fn window_event(&mut self, event_loop: &ActiveEventLoop, id: WindowId, event: WindowEvent) {
         match event {
             WindowEvent::CloseRequested => {
                 println!("The close button was pressed; stopping");
@@ -513,19 +763,29 @@ impl ApplicationHandler<Message> for WindowHandler {
         }
     }
 
-    fn user_event(&mut self, _event_loop: &winit::event_loop::ActiveEventLoop, event: Message) {
+    fn user_event(&mut self, event_loop: &winit::event_loop::ActiveEventLoop, event: Message) {
         match event {
             // Receive a frame
-            Message::Frame(info, buffer, window_id) => {
-                // ! This might be slow?
-                let window_data = self.windows.get(&window_id).expect("a value");
-
-                if let Ok(frame) = gst_gl::GLVideoFrame::from_buffer_readable(buffer, &info) {
-                    window_data.redraw(frame);
+            Message::FrameReady(window_id) => {
+                let Some(window_data) = self.windows.get(&window_id) else {
+                    return;
+                };
+                window_data.frame_pending.store(false, Ordering::SeqCst);
+                let Some(frame) = window_data.next_frame() else {
+                    return;
+                };
+                window_data.redraw(frame);
+                // frames queued while this one was drawn only woke the event loop once
+                if !window_data.frame_pending.swap(true, Ordering::SeqCst) {
+                    let _ = self.event_proxy.send_event(Message::FrameReady(window_id));
                 }
             }
             // Handle all pending messages when we are awaken by set_sync_handler
-            Message::BusMessage(msg) => WindowHandler::handle_message(msg),
+            Message::BusMessage(msg) => self.handle_message(msg),
+            Message::Exit => {
+                self.windows.clear();
+                event_loop.exit();
+            }
         }
     }
 }
@@ -1,26 +1,61 @@
 use std::collections::HashMap;
+use std::process::Command;
 
+use anyhow::{Error, Result};
 use glutin::surface::GlSurface;
 use gst::prelude::GstObjectExt;
 use gst_video::VideoFrameExt;
-use project_mapper_core::config::sink::{RefreshRate, Resolution, ResolutionJson};
+use project_mapper_core::config::sink::{
+    MonitorDescriptor, MonitorMatch, RefreshRate, Resolution, ResolutionJson, WindowPosition,
+};
+use raw_window_handle::{HasWindowHandle, RawWindowHandle};
+use winit::dpi::{PhysicalPosition, PhysicalSize};
 use winit::event_loop::ActiveEventLoop;
 use winit::monitor::{MonitorHandle, VideoModeHandle};
+use winit::window::Window;
 
 // internal for config
 pub struct MonitorData {
     pub name: String,
     pub monitor: MonitorHandle,
     pub mode_lookup: HashMap<ResolutionJson, HashMap<RefreshRate, VideoModeHandle>>,
+    pub descriptor: MonitorDescriptor,
+    pub primary: bool,
 }
 
-pub fn gather_monitor_info(event_loop: &ActiveEventLoop) -> HashMap<String, MonitorData> {
-    let mut monitor_map = HashMap::new();
+/// Name, position and current size of a monitor, cheap to read compared to its video modes
+pub type MonitorLayout = (Option<String>, PhysicalPosition<i32>, PhysicalSize<u32>);
+
+/// What is connected where, to tell whether `gather_monitor_info` is worth running again
+pub fn monitor_layout(event_loop: &ActiveEventLoop) -> Vec<MonitorLayout> {
+    event_loop
+        .available_monitors()
+        .map(|monitor| (monitor.name(), monitor.position(), monitor.size()))
+        .collect()
+}
+
+/// Every connected monitor from left to right, names aren't unique so this isn't keyed by them
+pub fn gather_monitor_info(event_loop: &ActiveEventLoop) -> Vec<MonitorData> {
+    let mut monitors = Vec::new();
+    let primary = event_loop.primary_monitor();
     for monitor in event_loop.available_monitors() {
+        // a monitor being unplugged can lose its name before it is gone
+        let Some(monitor_name) = monitor.name() else {
+            continue;
+        };
+        let monitor_name = sanitize_monitor_name(monitor_name);
+
         let mut resolution_map: HashMap<ResolutionJson, HashMap<RefreshRate, VideoModeHandle>> =
             HashMap::new();
+        // the current mode changes with exclusive full screen, the largest one stays put
+        let mut native_size = monitor.size();
         for monitor_handle in monitor.video_modes() {
             let size = monitor_handle.size();
+            if size.width as u64 * size.height as u64
+                > native_size.width as u64 * native_size.height as u64
+            {
+                native_size = size;
+            }
             let resolution = Resolution {
                 height: size.height,
                 width: size.width,
@@ -37,18 +72,103 @@ pub fn gather_monitor_info(event_loop: &ActiveEventLoop) -> HashMap<String, Moni
                 RefreshRate::from(monitor_handle.refresh_rate_millihertz());
             frequency_map.insert(refresh_rate_mhz, monitor_handle);
         }
-        let monitor_name = sanitize_monitor_name(monitor.name().expect("we have a name"));
+
+        let position = monitor.position();
+        let descriptor = MonitorDescriptor {
+            name: monitor_name.clone(),
+            native_id: native_id(&monitor),
+            position: Some(WindowPosition {
+                x: position.x,
+                y: position.y,
+            }),
+            native_resolution: Some(Resolution {
+                width: native_size.width,
+                height: native_size.height,
+            }),
+            index: None,
+        };
 
         let monitor_data = MonitorData {
             name: monitor_name.clone(),
+            primary: primary.as_ref() == Some(&monitor),
             monitor: monitor,
             mode_lookup: resolution_map,
+            descriptor: descriptor,
         };
-        monitor_map.insert(monitor_name, monitor_data);
+        monitors.push(monitor_data);
     }
-    monitor_map
+
+    // number the monitors from left to right, top to bottom for ones stacked vertically
+    monitors.sort_by_key(|data| {
+        let position = data.monitor.position();
+        (position.x, position.y)
+    });
+    for (index, data) in monitors.iter_mut().enumerate() {
+        data.descriptor.index = Some(index as u32);
+    }
+    monitors
+}
+
+#[cfg(target_os = "macos")]
+fn native_id(monitor: &MonitorHandle) -> Option<String> {
+    use winit::platform::macos::MonitorHandleExtMacOS;
+    Some(monitor.native_id().to_string())
+}
+
+#[cfg(target_os = "windows")]
+fn native_id(monitor: &MonitorHandle) -> Option<String> {
+    use winit::platform::windows::MonitorHandleExtWindows;
+    Some(monitor.native_id())
+}
+
+// X11 output names are all there is
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn native_id(_monitor: &MonitorHandle) -> Option<String> {
+    None
+}
+
+/// Finds the monitor a sink refers to, by name unless it has a monitor match, the name then
+/// settles what the match leaves open
+pub fn find_monitor<'a>(
+    name: &str,
+    monitor_match: Option<&MonitorMatch>,
+    monitors: &'a [MonitorData],
+) -> Option<&'a MonitorData> {
+    let Some(monitor_match) = monitor_match else {
+        return monitors.iter().find(|data| data.name == name);
+    };
+    let descriptors: Vec<MonitorDescriptor> = monitors
+        .iter()
+        .map(|data| data.descriptor.clone())
+        .collect();
+    let primary = monitors.iter().position(|data| data.primary);
+    monitor_match
+        .find_named(Some(name), &descriptors, primary)
+        .map(|position| &monitors[position])
 }
 
 fn sanitize_monitor_name(monitor_name: String) -> String {
     monitor_name.replace("\\", "")
 }
+
+/// Suspends screen blanking and DPMS until `window` is closed, xdg-screensaver resumes them then
+pub fn inhibit_screensaver(window: &Window) -> Result<()> {
+    let window_id = match window.window_handle()?.as_raw() {
+        RawWindowHandle::Xlib(handle) => handle.window as u64,
+        RawWindowHandle::Xcb(handle) => handle.window.get() as u64,
+        _ => {
+            return Err(Error::msg(
+                "Inhibiting the screensaver is only supported on X11",
+            ));
+        }
+    };
+    let status = Command::new("xdg-screensaver")
+        .arg("suspend")
+        .arg(format!("{window_id:#x}"))
+        .status()
+        .map_err(|err| Error::msg(format!("Failed to run xdg-screensaver: {err}")))?;
+    if !status.success() {
+        return Err(Error::msg(format!("xdg-screensaver failed with {status}")));
+    }
+    Ok(())
+}
@@ -0,0 +1,179 @@
+//! Layers a half transparent clip over a solid color and reads the mixed canvas back from a
+//! shared memory sink, the red of the clip has to blend with the blue below it. A clip that
+//! fails part way has to come back after its source is restarted.
+
+mod common;
+
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::{Duration, Instant};
+
+use gst::prelude::*;
+use project_mapper_core::config::{
+    runtime::RuntimeConfig,
+    sink::SinkConfig,
+    source::{Color, FailurePolicy, Playback, Rgba, SourceType, URI},
+};
+
+use common::{HEIGHT, WIDTH};
+
+// ARGB, half transparent red
+const CLIP_COLOR: u32 = 0x80ff0000;
+
+/// Writes a PNG coded QuickTime clip, which keeps the alpha channel unlike most video codecs.
+/// Frames after `good_frames` are corrupted when given
+fn write_clip(path: &str, good_frames: Option<u32>) {
+    let writer = gst::parse::launch(&format!(
+        "videotestsrc pattern=solid-color foreground-color={CLIP_COLOR} num-buffers=300 \
+         ! video/x-raw,format=RGBA,width={WIDTH},height={HEIGHT},framerate=30/1 \
+         ! pngenc name=encoder ! qtmux ! filesink location={path}"
+    ))
+    .unwrap();
+    if let Some(good_frames) = good_frames {
+        let frames = AtomicU32::new(0);
+        writer
+            .downcast_ref::<gst::Bin>()
+            .unwrap()
+            .by_name("encoder")
+            .unwrap()
+            .static_pad("src")
+            .unwrap()
+            .add_probe(gst::PadProbeType::BUFFER, move |_, info| {
+                if frames.fetch_add(1, Ordering::SeqCst) >= good_frames {
+                    if let Some(gst::PadProbeData::Buffer(ref mut buffer)) = info.data {
+                        corrupt_png(buffer.make_mut());
+                    }
+                }
+                gst::PadProbeReturn::Ok
+            });
+    }
+    common::run_to_eos(&writer);
+}
+
+/// Flips the first byte of image data, its checksum no longer matches so decoding fails
+fn corrupt_png(buffer: &mut gst::BufferRef) {
+    let mut map = buffer.map_writable().unwrap();
+    let data = map.as_mut_slice();
+    let idat = data
+        .windows(4)
+        .position(|chunk_type| chunk_type == b"IDAT")
+        .expect("PNG without image data");
+    data[idat + 4] ^= 0xff;
+}
+
+#[test]
+fn alpha_clip_blends_over_lower_layer() {
+    if !common::has_factories(&["shmsink", "pngenc", "pngdec", "qtmux", "qtdemux"]) {
+        return;
+    }
+
+    let clip_path = common::temp_path("alpha.mov");
+    write_clip(&clip_path, None);
+    let socket_path = common::temp_path("alpha");
+
+    let config = RuntimeConfig {
+        sinks: vec![SinkConfig {
+            name: "shm".to_owned(),
+            id: 1,
+            sink: common::shm_output(&socket_path),
+        }],
+        sources: vec![
+            common::source(
+                0,
+                SourceType::Color(Color {
+                    color: Rgba {
+                        red: 0,
+                        green: 0,
+                        blue: 255,
+                        alpha: 255,
+                    },
+                    ..Color::default()
+                }),
+            ),
+            common::source(
+                2,
+                SourceType::URI(URI {
+                    uri: glib::filename_to_uri(&clip_path, None).unwrap().to_string(),
+                    playback: Playback::default(),
+                    alpha: true,
+                }),
+            ),
+        ],
+        regions: vec![common::region(3, 0, 1, 0), common::region(4, 2, 1, 1)],
+    };
+    let runtime = common::spawn_runtime(&config, 30);
+    let (reader, appsink) = common::shm_reader(&socket_path);
+
+    // the background can show alone until the clip has prerolled, wait for the first mixed frame
+    let deadline = Instant::now() + Duration::from_secs(10);
+    let mut pixel = None;
+    while Instant::now() < deadline {
+        let Some(sample) = appsink.try_pull_sample(gst::ClockTime::from_seconds(1)) else {
+            continue;
+        };
+        let center_pixel = common::center_pixel(&sample);
+        pixel = Some(center_pixel);
+        if center_pixel[0] > 0 {
+            break;
+        }
+    }
+
+    reader.set_state(gst::State::Null).unwrap();
+    common::stop_runtime(runtime);
+    let _ = std::fs::remove_file(&socket_path);
+    let _ = std::fs::remove_file(&clip_path);
+
+    // half of the red over half of the blue, on an opaque canvas
+    let pixel = pixel.expect("no frame arrived from the runtime");
+    let close = |value: u8, expected: u8| value.abs_diff(expected) <= 10;
+    assert!(
+        close(pixel[0], 128) && close(pixel[1], 0) && close(pixel[2], 127) && pixel[3] == 255,
+        "expected red blended over blue, got {pixel:?}"
+    );
+}
+
+#[test]
+fn restarted_alpha_clip_recovers() {
+    if !common::has_factories(&["shmsink", "pngenc", "pngdec", "qtmux", "qtdemux"]) {
+        return;
+    }
+
+    // a second of good frames, then frames the decoder rejects
+    let clip_path = common::temp_path("failing-alpha.mov");
+    write_clip(&clip_path, Some(30));
+    let socket_path = common::temp_path("failing-alpha");
+
+    let mut config = common::single_region_config(
+        SourceType::URI(URI {
+            uri: glib::filename_to_uri(&clip_path, None).unwrap().to_string(),
+            playback: Playback::default(),
+            alpha: true,
+        }),
+        common::shm_output(&socket_path),
+    );
+    config.sources[0].failure = FailurePolicy {
+        retry_delay_ms: 100,
+        max_retry_delay_ms: 100,
+        ..FailurePolicy::default()
+    };
+    let (runtime, lines) = common::spawn_logged_runtime(&config, 30);
+
+    // the restart adds a new decoded pad, it has to reach the converter built for the first one
+    let deadline = Instant::now() + Duration::from_secs(20);
+    let mut recovered = false;
+    while let Some(timeout) = deadline.checked_duration_since(Instant::now()) {
+        match lines.recv_timeout(timeout) {
+            Ok(line) if line == "Source 0 recovered" => {
+                recovered = true;
+                break;
+            }
+            Ok(_) => {}
+            Err(_) => break,
+        }
+    }
+
+    common::stop_runtime(runtime);
+    let _ = std::fs::remove_file(&socket_path);
+    let _ = std::fs::remove_file(&clip_path);
+
+    assert!(recovered, "the alpha clip did not recover after a restart");
+}
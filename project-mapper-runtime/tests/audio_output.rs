@@ -0,0 +1,90 @@
+//! Plays a clip with an audio track through a URI source with a fake audio output and checks the
+//! source's volume, mute and offset settings end up on the audio branch.
+
+mod common;
+
+use gst::prelude::*;
+use project_mapper_core::config::source::{AudioConfig, AudioOutput, SourceType, URI};
+
+const OFFSET_MS: i64 = 250;
+
+/// Writes a short Matroska clip with raw video and a sine tone
+fn write_clip(path: &str) {
+    let writer = gst::parse::launch(&format!(
+        "videotestsrc num-buffers=30 ! video/x-raw,width=64,height=48,framerate=30/1 ! queue \
+         ! mux. audiotestsrc num-buffers=50 ! audio/x-raw,rate=48000,channels=2 ! queue ! mux. \
+         matroskamux name=mux ! filesink location={path}"
+    ))
+    .unwrap();
+    common::run_to_eos(&writer);
+}
+
+/// The single element of the pipeline made by `factory`
+fn find_element(pipeline: &gst::Pipeline, factory: &str) -> gst::Element {
+    let mut found: Vec<gst::Element> = pipeline
+        .iterate_recurse()
+        .into_iter()
+        .filter_map(|element| element.ok())
+        .filter(|element| {
+            element
+                .factory()
+                .is_some_and(|element_factory| element_factory.name() == factory)
+        })
+        .collect();
+    assert_eq!(found.len(), 1, "expected a single {factory}");
+    found.remove(0)
+}
+
+#[test]
+fn audio_settings_reach_the_audio_branch() {
+    if !common::has_factories(&["matroskamux"]) {
+        return;
+    }
+
+    let path = common::temp_path("audio.mkv");
+    write_clip(&path);
+
+    let uri = URI {
+        uri: glib::filename_to_uri(&path, None).unwrap().to_string(),
+        playback: Default::default(),
+        alpha: false,
+    };
+    let mut source_config = common::source(0, SourceType::URI(uri.clone()));
+    source_config.audio = Some(AudioConfig {
+        output: AudioOutput::Fake,
+        volume: 0.5,
+        mute: true,
+        offset_ms: OFFSET_MS,
+    });
+
+    // stands in for the source's input, video frames are not looked at
+    let video_sink = gst_app::AppSink::builder()
+        .sync(false)
+        .drop(true)
+        .max_buffers(1)
+        .build();
+    let pipeline = common::source_pipeline(&&uri, &source_config, &video_sink);
+
+    common::run_to_eos(pipeline.upcast_ref());
+    let _ = std::fs::remove_file(&path);
+
+    let volume = find_element(&pipeline, "volume");
+    assert_eq!(volume.property::<f64>("volume"), 0.5);
+    assert!(volume.property::<bool>("mute"));
+
+    // the offset is set on the queue in front of the audio conversion
+    let queue = find_element(&pipeline, "queue");
+    let offset = queue.static_pad("src").unwrap().offset();
+    assert_eq!(
+        offset,
+        OFFSET_MS * gst::ClockTime::MSECOND.nseconds() as i64
+    );
+
+    let audio_sink = find_element(&pipeline, "fakesink");
+    assert!(
+        audio_sink
+            .property::<Option<gst::Sample>>("last-sample")
+            .is_some(),
+        "no audio reached the fake audio sink"
+    );
+}
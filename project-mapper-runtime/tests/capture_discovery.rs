@@ -0,0 +1,135 @@
+//! Registers a fake camera through a device provider and checks capture sources find it by path
+//! and by name the way they would a V4L2 device.
+
+use gst::prelude::*;
+use gst::subclass::prelude::*;
+use project_mapper_core::config::source::Capture;
+use project_mapper_runtime::SourceTypeConstructor;
+
+const DEVICE_PATH: &str = "/dev/project-mapper-fake-camera";
+
+mod imp {
+    use std::sync::LazyLock;
+
+    use super::*;
+
+    #[derive(Default)]
+    pub struct FakeCamera;
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for FakeCamera {
+        const NAME: &'static str = "ProjectMapperFakeCamera";
+        type Type = super::FakeCamera;
+        type ParentType = gst::Device;
+    }
+
+    impl ObjectImpl for FakeCamera {}
+    impl GstObjectImpl for FakeCamera {}
+
+    impl DeviceImpl for FakeCamera {
+        fn create_element(&self, name: Option<&str>) -> Result<gst::Element, gst::LoggableError> {
+            gst::ElementFactory::make("videotestsrc")
+                .name_if_some(name)
+                .build()
+                .map_err(|_| gst::loggable_error!(gst::CAT_RUST, "no videotestsrc"))
+        }
+    }
+
+    #[derive(Default)]
+    pub struct FakeCameraProvider;
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for FakeCameraProvider {
+        const NAME: &'static str = "ProjectMapperFakeCameraProvider";
+        type Type = super::FakeCameraProvider;
+        type ParentType = gst::DeviceProvider;
+    }
+
+    impl ObjectImpl for FakeCameraProvider {}
+    impl GstObjectImpl for FakeCameraProvider {}
+
+    impl DeviceProviderImpl for FakeCameraProvider {
+        fn metadata() -> Option<&'static gst::subclass::DeviceProviderMetadata> {
+            static METADATA: LazyLock<gst::subclass::DeviceProviderMetadata> =
+                LazyLock::new(|| {
+                    gst::subclass::DeviceProviderMetadata::new(
+                        "Fake Camera Provider",
+                        "Video/Source",
+                        "Lists a single fake camera",
+                        "Project Mapper",
+                    )
+                });
+            Some(&*METADATA)
+        }
+
+        fn probe(&self) -> Vec<gst::Device> {
+            vec![super::FakeCamera::new().upcast()]
+        }
+    }
+}
+
+glib::wrapper! {
+    pub struct FakeCamera(ObjectSubclass<imp::FakeCamera>) @extends gst::Device, gst::Object;
+}
+
+impl FakeCamera {
+    fn new() -> Self {
+        let caps = gst::Caps::builder("video/x-raw")
+            .field("format", "YUY2")
+            .field("width", 640i32)
+            .field("height", 480i32)
+            .field("framerate", gst::Fraction::new(30, 1))
+            .build();
+        let properties = gst::Structure::builder("properties")
+            .field("device.path", DEVICE_PATH)
+            .build();
+        glib::Object::builder()
+            .property("display-name", "Fake Camera")
+            .property("device-class", "Video/Source")
+            .property("caps", &caps)
+            .property("properties", &properties)
+            .build()
+    }
+}
+
+glib::wrapper! {
+    pub struct FakeCameraProvider(ObjectSubclass<imp::FakeCameraProvider>)
+        @extends gst::DeviceProvider, gst::Object;
+}
+
+/// Builds a capture source for `device` and returns the element reading from the device
+fn capture_src(device: &str) -> gst::Element {
+    let capture = Capture {
+        device: device.to_owned(),
+        ..Capture::default()
+    };
+    let element = (&capture).create_element("0".to_owned()).unwrap();
+    element
+        .downcast::<gst::Bin>()
+        .unwrap()
+        .by_name("capture-0-src")
+        .expect("the capture source has no device element")
+}
+
+#[test]
+fn capture_source_opens_provider_devices() {
+    gst::init().unwrap();
+    gst::DeviceProvider::register(
+        None,
+        "projectmapperfakecameraprovider",
+        gst::Rank::PRIMARY,
+        FakeCameraProvider::static_type(),
+    )
+    .unwrap();
+
+    // the fake camera hands out a test pattern, the fallback would be a v4l2src
+    for device in [DEVICE_PATH, "Fake Camera"] {
+        let src = capture_src(device);
+        let factory = src.factory().unwrap();
+        assert_eq!(
+            factory.name(),
+            "videotestsrc",
+            "{device} was not found through the device provider"
+        );
+    }
+}
@@ -0,0 +1,101 @@
+//! Builds color and text sources headless and checks that their settings reach the GStreamer
+//! elements, the color down to the pixels it produces.
+
+mod common;
+
+use gst::prelude::*;
+use project_mapper_core::config::{
+    sink::Resolution,
+    source::{Color, HorizontalAlignment, Rgba, SourceType, Text, VerticalAlignment},
+};
+use project_mapper_runtime::SourceTypeConstructor;
+
+use common::{HEIGHT, WIDTH};
+
+/// Nick of an enum property, the way it would be set from a string
+fn enum_nick(element: &gst::Element, property: &str) -> String {
+    let value = element.property_value(property);
+    let (_, enum_value) = glib::EnumValue::from_value(&value).unwrap();
+    enum_value.nick().to_owned()
+}
+
+#[test]
+fn color_reaches_videotestsrc() {
+    if !common::has_factories(&["videotestsrc"]) {
+        return;
+    }
+
+    let color = Color {
+        color: Rgba {
+            red: 10,
+            green: 200,
+            blue: 30,
+            alpha: 255,
+        },
+        resolution: Some(Resolution {
+            width: WIDTH,
+            height: HEIGHT,
+        }),
+        framerate: Some(30),
+    };
+    let source_config = common::source(0, SourceType::Color(color.clone()));
+    let appsink = gst_app::AppSink::builder()
+        .caps(
+            &gst::Caps::builder("video/x-raw")
+                .field("format", "RGBA")
+                .build(),
+        )
+        .sync(false)
+        .build();
+    let pipeline = common::source_pipeline(&&color, &source_config, &appsink);
+
+    let src = pipeline.by_name("color-0-src").unwrap();
+    assert_eq!(
+        src.property::<u32>("foreground-color"),
+        color.color.to_argb()
+    );
+
+    pipeline.set_state(gst::State::Playing).unwrap();
+    let sample = appsink.try_pull_sample(gst::ClockTime::from_seconds(5));
+    pipeline.set_state(gst::State::Null).unwrap();
+
+    let sample = sample.expect("the color source produced no frame");
+    assert_eq!(common::center_pixel(&sample), [10, 200, 30, 255]);
+}
+
+#[test]
+fn text_alignment_maps_to_textoverlay() {
+    if !common::has_factories(&["videotestsrc", "textoverlay"]) {
+        return;
+    }
+
+    let horizontal = [
+        (HorizontalAlignment::Left, "left"),
+        (HorizontalAlignment::Center, "center"),
+        (HorizontalAlignment::Right, "right"),
+    ];
+    let vertical = [
+        (VerticalAlignment::Top, "top"),
+        (VerticalAlignment::Center, "center"),
+        (VerticalAlignment::Bottom, "bottom"),
+    ];
+    for (horizontal_alignment, halignment) in &horizontal {
+        for (vertical_alignment, valignment) in &vertical {
+            let text = Text {
+                text: "Mapped".to_owned(),
+                horizontal_alignment: horizontal_alignment.clone(),
+                vertical_alignment: vertical_alignment.clone(),
+                ..Text::default()
+            };
+            let element = (&text).create_element("0".to_owned()).unwrap();
+            let overlay = element
+                .downcast_ref::<gst::Bin>()
+                .unwrap()
+                .by_name("text-0-overlay")
+                .unwrap();
+
+            assert_eq!(enum_nick(&overlay, "halignment"), *halignment);
+            assert_eq!(enum_nick(&overlay, "valignment"), *valignment);
+        }
+    }
+}
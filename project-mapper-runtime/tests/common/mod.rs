@@ -0,0 +1,233 @@
+//! Helpers shared by the integration tests: building small configs, driving the runtime binary
+//! headless and reading back what its sinks produce.
+
+// every test only uses some of the helpers
+#![allow(dead_code)]
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::UdpSocket;
+use std::path::Path;
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use gst::prelude::*;
+use project_mapper_core::config::{
+    runtime::{RegionConfig, RegionType, RuntimeConfig},
+    sink::{Resolution, SharedMemoryOutput, SinkConfig, SinkType},
+    source::{FailurePolicy, SourceConfig, SourceType},
+};
+use project_mapper_runtime::SourceTypeConstructor;
+
+pub const WIDTH: u32 = 64;
+pub const HEIGHT: u32 = 48;
+
+/// Initializes GStreamer and tells whether every factory is installed, tests skip when not
+pub fn has_factories(factories: &[&str]) -> bool {
+    gst::init().unwrap();
+    match factories
+        .iter()
+        .find(|factory| gst::ElementFactory::find(factory).is_none())
+    {
+        Some(factory) => {
+            eprintln!("{factory} is not installed, skipping");
+            false
+        }
+        None => true,
+    }
+}
+
+/// A port nothing is listening on right now
+pub fn free_port() -> u16 {
+    UdpSocket::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port()
+}
+
+/// A path in the temp directory no other test process uses
+pub fn temp_path(name: &str) -> String {
+    std::env::temp_dir()
+        .join(format!("project-mapper-{}-{name}", std::process::id()))
+        .to_string_lossy()
+        .to_string()
+}
+
+pub fn source(id: u32, source: SourceType) -> SourceConfig {
+    SourceConfig {
+        name: format!("source-{id}"),
+        id: id,
+        source: source,
+        audio: None,
+        failure: FailurePolicy::default(),
+    }
+}
+
+pub fn region(id: u32, source: u32, sink: u32, layer: u32) -> RegionConfig {
+    RegionConfig {
+        name: format!("region-{id}"),
+        id: id,
+        region: RegionType::Display {
+            source: source,
+            sink: sink,
+            layer: layer,
+        },
+    }
+}
+
+/// Builds a source into a pipeline of its own, `input` stands in for the source's input
+pub fn source_pipeline(
+    constructor: &dyn SourceTypeConstructor,
+    source_config: &SourceConfig,
+    input: &gst_app::AppSink,
+) -> gst::Pipeline {
+    constructor.validate().unwrap();
+    let pipeline = gst::Pipeline::default();
+    let element = constructor
+        .create_element(source_config.id.to_string())
+        .unwrap();
+    pipeline.add_many([&element, input.upcast_ref()]).unwrap();
+    constructor
+        .initialize_element(source_config, &element, input.upcast_ref(), &pipeline)
+        .unwrap();
+    pipeline
+}
+
+/// Shows `source` as source 0 on `sink` as sink 1
+pub fn single_region_config(source_type: SourceType, sink: SinkType) -> RuntimeConfig {
+    RuntimeConfig {
+        sinks: vec![SinkConfig {
+            name: "sink".to_owned(),
+            id: 1,
+            sink: sink,
+        }],
+        sources: vec![source(0, source_type)],
+        regions: vec![region(2, 0, 1, 0)],
+    }
+}
+
+/// A `WIDTH`x`HEIGHT` shared memory sink, read it back with `shm_reader`
+pub fn shm_output(socket_path: &str) -> SinkType {
+    SinkType::SharedMemory(SharedMemoryOutput {
+        socket_path: socket_path.to_owned(),
+        resolution: Resolution {
+            width: WIDTH,
+            height: HEIGHT,
+        },
+        framerate: 30,
+        buffer_frames: 4,
+    })
+}
+
+fn runtime_command(duration_s: u64) -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_project-mapper-runtime"));
+    command
+        .args(["run", "-", "--duration-s", &duration_s.to_string()])
+        .stdin(Stdio::piped());
+    command
+}
+
+fn send_config(runtime: &mut Child, config: &RuntimeConfig) {
+    // closing stdin ends the config
+    runtime
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(serde_json::to_string(config).unwrap().as_bytes())
+        .unwrap();
+}
+
+/// Runs `config` in the runtime binary, which runs headless when no sink needs a window
+pub fn spawn_runtime(config: &RuntimeConfig, duration_s: u64) -> Child {
+    let mut runtime = runtime_command(duration_s)
+        .stdout(Stdio::null())
+        .spawn()
+        .unwrap();
+    send_config(&mut runtime, config);
+    runtime
+}
+
+/// Like `spawn_runtime` but hands every line the runtime prints to the returned receiver
+pub fn spawn_logged_runtime(
+    config: &RuntimeConfig,
+    duration_s: u64,
+) -> (Child, mpsc::Receiver<String>) {
+    let mut runtime = runtime_command(duration_s)
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    send_config(&mut runtime, config);
+
+    // keep reading so the runtime never blocks on a full pipe
+    let stdout = runtime.stdout.take().unwrap();
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        for line in BufReader::new(stdout).lines() {
+            let Ok(line) = line else {
+                return;
+            };
+            let _ = sender.send(line);
+        }
+    });
+    (runtime, receiver)
+}
+
+pub fn stop_runtime(mut runtime: Child) {
+    let _ = runtime.kill();
+    let _ = runtime.wait();
+}
+
+/// Waits for the shared memory sink at `socket_path` and starts reading its RGBA frames
+pub fn shm_reader(socket_path: &str) -> (gst::Pipeline, gst_app::AppSink) {
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while !Path::new(socket_path).exists() && Instant::now() < deadline {
+        thread::sleep(Duration::from_millis(50));
+    }
+
+    let reader = gst::parse::launch(&format!(
+        "shmsrc socket-path={socket_path} is-live=true do-timestamp=true \
+         ! video/x-raw,format=RGBA,width={WIDTH},height={HEIGHT},framerate=30/1 \
+         ! appsink name=reader"
+    ))
+    .unwrap()
+    .downcast::<gst::Pipeline>()
+    .unwrap();
+    let appsink = reader
+        .by_name("reader")
+        .unwrap()
+        .downcast::<gst_app::AppSink>()
+        .unwrap();
+    reader.set_state(gst::State::Playing).unwrap();
+    (reader, appsink)
+}
+
+/// RGBA of the center pixel of a `WIDTH`x`HEIGHT` frame
+pub fn center_pixel(sample: &gst::Sample) -> [u8; 4] {
+    let buffer = sample.buffer().unwrap().map_readable().unwrap();
+    let center = ((HEIGHT / 2 * WIDTH + WIDTH / 2) * 4) as usize;
+    [
+        buffer[center],
+        buffer[center + 1],
+        buffer[center + 2],
+        buffer[center + 3],
+    ]
+}
+
+/// Plays a pipeline until it ends, panics when it fails or takes too long
+pub fn run_to_eos(pipeline: &gst::Element) {
+    pipeline.set_state(gst::State::Playing).unwrap();
+    let message = pipeline
+        .bus()
+        .unwrap()
+        .timed_pop_filtered(
+            gst::ClockTime::from_seconds(10),
+            &[gst::MessageType::Eos, gst::MessageType::Error],
+        )
+        .expect("pipeline did not finish in time");
+    pipeline.set_state(gst::State::Null).unwrap();
+    if let gst::MessageView::Error(err) = message.view() {
+        panic!("pipeline failed: {}", err.error());
+    }
+}
@@ -0,0 +1,125 @@
+//! Registers a source kind the way an application embedding the runtime would, then checks the
+//! latest registration builds a config using it and shows its frames.
+
+mod common;
+
+use std::thread;
+use std::time::Duration;
+
+use gst::prelude::*;
+use project_mapper_core::config::{
+    options::SourceTypeOptions,
+    source::{Custom, SourceConfig, SourceType},
+};
+use project_mapper_runtime::{SourceKind, SourceTypeConstructor, register_source_kind};
+
+/// A single colored `videotestsrc` pattern, configured with `{"pattern": "<nick>"}`
+struct Solid {
+    pattern: String,
+}
+
+impl SourceTypeConstructor for Solid {
+    fn validate(&self) -> Result<(), glib::BoolError> {
+        if self.pattern.is_empty() {
+            return Err(glib::bool_error!("Solid sources need a pattern"));
+        }
+        Ok(())
+    }
+
+    fn create_element(&self, id: String) -> Result<gst::Element, glib::BoolError> {
+        gst::ElementFactory::make("videotestsrc")
+            .name(format!("solid-{}", id))
+            .property("is-live", true)
+            .property_from_str("pattern", &self.pattern)
+            .build()
+    }
+
+    fn initialize_element(
+        &self,
+        _source_config: &SourceConfig,
+        src_element: &gst::Element,
+        sink_element: &gst::Element,
+        _pipeline: &gst::Pipeline,
+    ) -> Result<(), glib::error::BoolError> {
+        src_element.link(sink_element)
+    }
+}
+
+struct SolidKind {
+    // ignores the settings, stands in for an outdated registration
+    fixed_pattern: Option<&'static str>,
+}
+
+impl SourceKind for SolidKind {
+    fn name(&self) -> &str {
+        "solid"
+    }
+
+    fn constructor<'a>(
+        &self,
+        source: &'a SourceType,
+    ) -> Option<Box<dyn SourceTypeConstructor + 'a>> {
+        let SourceType::Custom(custom) = source else {
+            return None;
+        };
+        if custom.kind != self.name() {
+            return None;
+        }
+        let pattern = self
+            .fixed_pattern
+            .or(custom.settings["pattern"].as_str())
+            .unwrap_or_default();
+        Some(Box::new(Solid {
+            pattern: pattern.to_owned(),
+        }))
+    }
+
+    fn options(&self) -> anyhow::Result<Option<SourceTypeOptions>> {
+        Ok(Some(SourceTypeOptions::Custom {
+            kind: self.name().to_owned(),
+            settings: serde_json::json!({}),
+        }))
+    }
+}
+
+#[test]
+fn registered_source_kind_is_built() {
+    if !common::has_factories(&["shmsink"]) {
+        return;
+    }
+
+    // the second registration overrides the first, which would show blue
+    register_source_kind(SolidKind {
+        fixed_pattern: Some("blue"),
+    });
+    register_source_kind(SolidKind {
+        fixed_pattern: None,
+    });
+
+    let socket_path = common::temp_path("custom-source");
+    let config = common::single_region_config(
+        SourceType::Custom(Custom {
+            kind: "solid".to_owned(),
+            settings: serde_json::json!({ "pattern": "red" }),
+        }),
+        common::shm_output(&socket_path),
+    );
+
+    // the kind is only registered in this process so the runtime has to run here as well
+    thread::spawn(move || {
+        project_mapper_runtime::run_config(config, Some(Duration::from_secs(15))).unwrap();
+    });
+
+    let (reader, appsink) = common::shm_reader(&socket_path);
+    let sample = appsink.try_pull_sample(gst::ClockTime::from_seconds(5));
+
+    reader.set_state(gst::State::Null).unwrap();
+    let _ = std::fs::remove_file(&socket_path);
+
+    let sample = sample.expect("no frame arrived from the custom source");
+    let pixel = common::center_pixel(&sample);
+    assert!(
+        pixel[0] > 200 && pixel[1] < 50 && pixel[2] < 50,
+        "expected the red pattern, got {pixel:?}"
+    );
+}
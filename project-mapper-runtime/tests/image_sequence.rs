@@ -0,0 +1,108 @@
+//! Plays generated PNG sequences through the image sequence source and counts the frames that
+//! come out, the way a region would receive them.
+
+mod common;
+
+use gst::prelude::*;
+use project_mapper_core::config::source::{ImageSequence, SourceType};
+use project_mapper_runtime::SourceTypeConstructor;
+
+use common::{HEIGHT, WIDTH};
+
+const PATTERN: &str = "frame_%05d.png";
+
+/// Writes `count` frames named after `PATTERN` into a fresh directory
+fn write_frames(name: &str, count: u32) -> String {
+    let directory = common::temp_path(name);
+    let _ = std::fs::remove_dir_all(&directory);
+    std::fs::create_dir_all(&directory).unwrap();
+    let writer = gst::parse::launch(&format!(
+        "videotestsrc num-buffers={count} \
+         ! video/x-raw,format=RGB,width={WIDTH},height={HEIGHT},framerate=30/1 \
+         ! pngenc ! multifilesink location={directory}/{PATTERN}"
+    ))
+    .unwrap();
+    common::run_to_eos(&writer);
+    directory
+}
+
+fn sequence(directory: &str) -> ImageSequence {
+    ImageSequence {
+        directory: directory.to_owned(),
+        pattern: PATTERN.to_owned(),
+        framerate: Some(30),
+        ..ImageSequence::default()
+    }
+}
+
+/// Plays the sequence and pulls up to `limit` frames, tells how many arrived before it ended
+fn pull_frames(sequence: ImageSequence, limit: u32) -> (u32, bool) {
+    let source_config = common::source(0, SourceType::ImageSequence(sequence.clone()));
+    let appsink = gst_app::AppSink::builder().sync(false).build();
+    let pipeline = common::source_pipeline(&&sequence, &source_config, &appsink);
+    pipeline.set_state(gst::State::Playing).unwrap();
+
+    let mut frames = 0;
+    while frames < limit
+        && appsink
+            .try_pull_sample(gst::ClockTime::from_seconds(5))
+            .is_some()
+    {
+        frames += 1;
+    }
+    let ended = appsink.is_eos();
+    pipeline.set_state(gst::State::Null).unwrap();
+    (frames, ended)
+}
+
+#[test]
+fn sequence_plays_from_start_to_end_frame() {
+    if !common::has_factories(&["multifilesrc", "multifilesink", "pngenc", "pngdec"]) {
+        return;
+    }
+
+    let directory = write_frames("sequence-range", 8);
+    let (frames, ended) = pull_frames(
+        ImageSequence {
+            start_frame: 2,
+            end_frame: Some(5),
+            ..sequence(&directory)
+        },
+        100,
+    );
+    let _ = std::fs::remove_dir_all(&directory);
+
+    // both ends are included
+    assert_eq!(frames, 4);
+    assert!(ended, "the sequence did not end after its end frame");
+}
+
+#[test]
+fn looping_sequence_keeps_playing() {
+    if !common::has_factories(&["multifilesrc", "multifilesink", "pngenc", "pngdec"]) {
+        return;
+    }
+
+    let directory = write_frames("sequence-loop", 3);
+    let (frames, ended) = pull_frames(
+        ImageSequence {
+            looping: true,
+            end_frame: Some(2),
+            ..sequence(&directory)
+        },
+        10,
+    );
+    let _ = std::fs::remove_dir_all(&directory);
+
+    assert_eq!(frames, 10);
+    assert!(!ended, "a looping sequence ended");
+}
+
+#[test]
+fn missing_directory_is_rejected() {
+    gst::init().unwrap();
+
+    let directory = common::temp_path("sequence-missing");
+    let _ = std::fs::remove_dir_all(&directory);
+    assert!((&sequence(&directory)).validate().is_err());
+}
@@ -0,0 +1,70 @@
+//! Streams H.264 over RTP to a local port and plays it back through a `NetworkStream` source,
+//! checking frames arrive and the latency and timeout settings end up on the built elements.
+
+mod common;
+
+use gst::prelude::*;
+use project_mapper_core::config::source::{
+    NetworkStream, SourceType, StreamProtocol, StreamTransport,
+};
+
+const LATENCY_MS: u32 = 50;
+const TIMEOUT_MS: u64 = 2000;
+
+#[test]
+fn rtp_stream_delivers_frames() {
+    if !common::has_factories(&["x264enc", "rtph264pay", "rtph264depay", "avdec_h264"]) {
+        return;
+    }
+
+    let port = common::free_port();
+    let stream = NetworkStream {
+        protocol: StreamProtocol::Rtp,
+        uri: format!("udp://127.0.0.1:{port}"),
+        latency_ms: Some(LATENCY_MS),
+        transport: StreamTransport::default(),
+        timeout_ms: Some(TIMEOUT_MS),
+        caps: Some(
+            "application/x-rtp,media=video,encoding-name=H264,clock-rate=90000,payload=96"
+                .to_owned(),
+        ),
+    };
+    let source_config = common::source(0, SourceType::NetworkStream(stream.clone()));
+
+    let video_sink = gst_app::AppSink::builder()
+        .sync(false)
+        .drop(true)
+        .max_buffers(1)
+        .build();
+    let pipeline = common::source_pipeline(&&stream, &source_config, &video_sink);
+
+    // start listening before anything is sent
+    pipeline.set_state(gst::State::Playing).unwrap();
+
+    let sender = gst::parse::launch(&format!(
+        "videotestsrc is-live=true ! video/x-raw,width=64,height=48,framerate=30/1 \
+         ! x264enc tune=zerolatency key-int-max=10 ! rtph264pay pt=96 config-interval=1 \
+         ! udpsink host=127.0.0.1 port={port}"
+    ))
+    .unwrap();
+    sender.set_state(gst::State::Playing).unwrap();
+
+    let sample = video_sink.try_pull_sample(gst::ClockTime::from_seconds(10));
+
+    sender.set_state(gst::State::Null).unwrap();
+    pipeline.set_state(gst::State::Null).unwrap();
+
+    let sample = sample.expect("no frame arrived from the RTP stream");
+    let caps = sample.caps().unwrap();
+    assert!(caps.structure(0).unwrap().name().starts_with("video/"));
+
+    // by_name looks inside the stream's bin as well
+    let jitterbuffer = pipeline.by_name("stream-0-jitterbuffer").unwrap();
+    assert_eq!(jitterbuffer.property::<u32>("latency"), LATENCY_MS);
+
+    let udpsrc = pipeline.by_name("stream-0-src").unwrap();
+    assert_eq!(
+        udpsrc.property::<u64>("timeout"),
+        TIMEOUT_MS * gst::ClockTime::MSECOND.nseconds()
+    );
+}
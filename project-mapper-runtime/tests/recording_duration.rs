@@ -0,0 +1,75 @@
+//! Records a test pattern headless for a fixed time and checks the file holds that much video,
+//! the test pattern isn't live so nothing but the recording itself keeps it to real time.
+
+mod common;
+
+use std::thread;
+use std::time::{Duration, Instant};
+
+use gst::prelude::*;
+use project_mapper_core::config::{
+    sink::{Recording, RecordingFormat, Resolution, SinkType},
+    source::{SourceType, Test},
+};
+
+const DURATION_S: u64 = 3;
+
+/// Duration of a recorded file as reported by its demuxer
+fn file_duration(path: &str) -> gst::ClockTime {
+    let pipeline =
+        gst::parse::launch(&format!("filesrc location={path} ! decodebin ! fakesink")).unwrap();
+    pipeline.set_state(gst::State::Paused).unwrap();
+    let (result, _, _) = pipeline.state(gst::ClockTime::from_seconds(10));
+    result.expect("the recording can't be played");
+    let duration = pipeline.query_duration::<gst::ClockTime>();
+    pipeline.set_state(gst::State::Null).unwrap();
+    duration.expect("the recording has no duration")
+}
+
+#[test]
+fn recording_lasts_the_requested_duration() {
+    if !common::has_factories(&["avenc_ffv1", "matroskamux"]) {
+        return;
+    }
+
+    let path = common::temp_path("recording.mkv");
+    let config = common::single_region_config(
+        SourceType::Test(Test::default()),
+        SinkType::File(Recording {
+            path: path.clone(),
+            format: RecordingFormat::Matroska,
+            resolution: Resolution {
+                width: 64,
+                height: 48,
+            },
+            framerate: 30,
+        }),
+    );
+    let mut runtime = common::spawn_runtime(&config, DURATION_S);
+
+    // the runtime stops itself and finalizes the file
+    let deadline = Instant::now() + Duration::from_secs(DURATION_S + 30);
+    let status = loop {
+        if let Some(status) = runtime.try_wait().unwrap() {
+            break Some(status);
+        }
+        if Instant::now() > deadline {
+            let _ = runtime.kill();
+            break None;
+        }
+        thread::sleep(Duration::from_millis(100));
+    };
+    let status = status.expect("the runtime did not stop after its duration");
+    assert!(status.success(), "the runtime failed: {status}");
+
+    let duration = file_duration(&path);
+    let _ = std::fs::remove_file(&path);
+
+    // startup and the EOS drain may add or lose a few frames
+    let expected = gst::ClockTime::from_seconds(DURATION_S);
+    let tolerance = gst::ClockTime::from_mseconds(750);
+    assert!(
+        duration + tolerance >= expected && duration <= expected + tolerance,
+        "expected about {expected} of video, got {duration}"
+    );
+}
@@ -0,0 +1,53 @@
+//! Pushes frames from a second local pipeline through the shared memory source into an appsink
+//! standing in for a region's sink.
+
+use std::str::FromStr;
+
+use gst::prelude::*;
+use project_mapper_core::config::source::SharedMemory;
+use project_mapper_runtime::SourceTypeConstructor;
+
+const CAPS: &str = "video/x-raw,format=RGBA,width=64,height=48,framerate=30/1";
+
+#[test]
+fn shared_memory_source_receives_frames() {
+    gst::init().unwrap();
+    if gst::ElementFactory::find("shmsrc").is_none() {
+        eprintln!("shm plugin is not installed, skipping");
+        return;
+    }
+
+    let socket_path =
+        std::env::temp_dir().join(format!("project-mapper-shm-test-{}", std::process::id()));
+    let socket_path = socket_path.to_string_lossy().to_string();
+
+    let producer = gst::parse::launch(&format!(
+        "videotestsrc is-live=true ! {CAPS} ! shmsink socket-path={socket_path} \
+         shm-size=2000000 wait-for-connection=false sync=true"
+    ))
+    .unwrap();
+    producer.set_state(gst::State::Playing).unwrap();
+
+    let source = SharedMemory {
+        socket_path: socket_path.clone(),
+        caps: CAPS.to_owned(),
+    };
+    (&source).validate().unwrap();
+    let element = (&source).create_element("0".to_owned()).unwrap();
+
+    let appsink = gst_app::AppSink::builder().build();
+    let consumer = gst::Pipeline::default();
+    consumer.add_many([&element, appsink.upcast_ref()]).unwrap();
+    element.link(&appsink).unwrap();
+    consumer.set_state(gst::State::Playing).unwrap();
+
+    let sample = appsink.try_pull_sample(gst::ClockTime::from_seconds(5));
+
+    consumer.set_state(gst::State::Null).unwrap();
+    producer.set_state(gst::State::Null).unwrap();
+
+    let sample = sample.expect("no frame arrived through shared memory");
+    let caps = sample.caps().expect("sample without caps");
+    assert!(caps.can_intersect(&gst::Caps::from_str(CAPS).unwrap()));
+    assert_eq!(sample.buffer().unwrap().size(), 64 * 48 * 4);
+}
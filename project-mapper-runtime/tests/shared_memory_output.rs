@@ -0,0 +1,36 @@
+//! Runs the runtime headless with a shared memory sink and reads its frames back from this
+//! process, the way a recorder or analysis tool on the show machine would.
+
+mod common;
+
+use gst::prelude::*;
+use project_mapper_core::config::source::{SourceType, Test};
+
+use common::{HEIGHT, WIDTH};
+
+#[test]
+fn shared_memory_sink_publishes_frames() {
+    if !common::has_factories(&["shmsink"]) {
+        return;
+    }
+
+    let socket_path = common::temp_path("shm-output");
+    let config = common::single_region_config(
+        SourceType::Test(Test::default()),
+        common::shm_output(&socket_path),
+    );
+    let runtime = common::spawn_runtime(&config, 30);
+
+    let (reader, appsink) = common::shm_reader(&socket_path);
+    let sample = appsink.try_pull_sample(gst::ClockTime::from_seconds(5));
+
+    reader.set_state(gst::State::Null).unwrap();
+    common::stop_runtime(runtime);
+    let _ = std::fs::remove_file(&socket_path);
+
+    let sample = sample.expect("no frame arrived from the runtime");
+    assert_eq!(
+        sample.buffer().unwrap().size(),
+        (WIDTH * HEIGHT * 4) as usize
+    );
+}
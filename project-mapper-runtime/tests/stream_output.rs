@@ -0,0 +1,69 @@
+//! Runs the runtime headless with an RTP stream sink to a local port and decodes what arrives,
+//! the way a remote display machine would receive it.
+
+mod common;
+
+use gst::prelude::*;
+use project_mapper_core::config::{
+    sink::{OutputProtocol, Resolution, SinkType, StreamOutput},
+    source::{SourceType, Test},
+};
+
+use common::{HEIGHT, WIDTH};
+
+#[test]
+fn rtp_stream_sink_sends_decodable_frames() {
+    if !common::has_factories(&[
+        "x264enc",
+        "h264parse",
+        "rtph264pay",
+        "rtph264depay",
+        "avdec_h264",
+    ]) {
+        return;
+    }
+
+    let port = common::free_port();
+    // listen before the runtime starts sending, RTP has no handshake to wait for
+    let receiver = gst::parse::launch(&format!(
+        "udpsrc address=127.0.0.1 port={port} \
+         caps=\"application/x-rtp,media=video,encoding-name=H264,clock-rate=90000,payload=96\" \
+         ! rtph264depay ! avdec_h264 ! appsink name=receiver sync=false"
+    ))
+    .unwrap()
+    .downcast::<gst::Pipeline>()
+    .unwrap();
+    let appsink = receiver
+        .by_name("receiver")
+        .unwrap()
+        .downcast::<gst_app::AppSink>()
+        .unwrap();
+    receiver.set_state(gst::State::Playing).unwrap();
+
+    let config = common::single_region_config(
+        SourceType::Test(Test::default()),
+        SinkType::Stream(StreamOutput {
+            protocol: OutputProtocol::Rtp,
+            uri: format!("udp://127.0.0.1:{port}"),
+            resolution: Resolution {
+                width: WIDTH,
+                height: HEIGHT,
+            },
+            framerate: 30,
+            ..StreamOutput::default()
+        }),
+    );
+    let runtime = common::spawn_runtime(&config, 30);
+
+    // the first keyframe has to arrive before anything decodes
+    let sample = appsink.try_pull_sample(gst::ClockTime::from_seconds(15));
+
+    receiver.set_state(gst::State::Null).unwrap();
+    common::stop_runtime(runtime);
+
+    let sample = sample.expect("no frame was decoded from the stream");
+    let info = gst_video::VideoInfo::from_caps(sample.caps().unwrap()).unwrap();
+    assert_eq!((info.width(), info.height()), (WIDTH, HEIGHT));
+    // 4:2:0 decodes as I420, anything else means the encoder got a profile receivers may lack
+    assert_eq!(info.format(), gst_video::VideoFormat::I420);
+}